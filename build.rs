@@ -1,9 +1,12 @@
 extern crate skeptic;
 use std::{env, fs};
+use std::process::Command;
 
 
 #[cfg(unix)]
 fn main() {
+    type_name_cfg();
+
     if env::var("USE_SKEPTIC").is_ok() {
         // generates doc tests for `README.md`.
         skeptic::generate_doc_tests(&["README.md"]);
@@ -15,4 +18,26 @@ fn main() {
 
 #[cfg(not(unix))]
 fn main() {
+    type_name_cfg();
+}
+
+/// `std::any::type_name()` is available since rust 1.38
+fn type_name_cfg() {
+    println!("cargo:rustc-check-cfg=cfg(actix_type_name)");
+    if rustc_minor().map(|minor| minor >= 38).unwrap_or(false) {
+        println!("cargo:rustc-cfg=actix_type_name");
+    }
+}
+
+fn rustc_minor() -> Option<u32> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let output = match Command::new(rustc).arg("--version").output() {
+        Ok(output) => output,
+        Err(_) => return None,
+    };
+    // rustc 1.38.0 (625451e37 2019-09-23)
+    let version = String::from_utf8_lossy(&output.stdout).into_owned();
+    version.split(' ').nth(1)
+        .and_then(|version| version.split('.').nth(1))
+        .and_then(|minor| minor.parse().ok())
 }
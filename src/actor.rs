@@ -1,4 +1,5 @@
 use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
 use futures::{future, Stream};
 
 use fut::ActorFuture;
@@ -7,8 +8,10 @@ use address::{Addr, ActorAddress, Syn, Unsync};
 use context::Context;
 use handler::{Handler, Message};
 use stream::StreamHandler;
-use contextitems::{ActorMessageItem, ActorDelayedMessageItem, ActorMessageStreamItem};
-use utils::TimerFunc;
+use trace::{self, TraceContext};
+use contextitems::{ActorMessageItem, ActorDelayedMessageItem,
+                   ActorIntervalMessageItem, ActorMessageStreamItem};
+use utils::{IntervalFunc, TimerFunc};
 
 
 #[allow(unused_variables)]
@@ -76,13 +79,15 @@ pub trait Actor: Sized + 'static {
     /// left in the context.
     ///
     /// Actor could restore from stopping state by returning `false` value.
+    /// Reason of the stop is available with `ActorContext::stop_reason()`.
     fn stopping(&mut self, ctx: &mut Self::Context) -> bool {
         true
     }
 
     /// Method is called after an actor is stopped, it can be used to perform
     /// any needed cleanup work or spawning more actors. This is final state,
-    /// after this call actor get dropped.
+    /// after this call actor get dropped. Reason of the stop is available
+    /// with `ActorContext::stop_reason()`.
     fn stopped(&mut self, ctx: &mut Self::Context) {}
 
     /// Start new asynchronous actor, returns address of newly created actor.
@@ -167,6 +172,9 @@ pub trait Actor: Sized + 'static {
 pub trait Supervised: Actor {
 
     /// Method called when supervisor restarting failed actor
+    ///
+    /// `ActorContext::stop_reason()` returns reason of the failure,
+    /// i.e. `StopReason::Panicked` with panic message.
     fn restarting(&mut self, ctx: &mut <Self as Actor>::Context) {}
 }
 
@@ -189,21 +197,77 @@ impl ActorState {
     }
 }
 
+/// Reason of actor termination
+#[derive(PartialEq, Debug, Clone)]
+pub enum StopReason {
+    /// Actor stopped normally, i.e. with `ActorContext::stop()`
+    Normal,
+    /// All addresses of the actor get dropped and there is nothing to process
+    Disconnected,
+    /// Actor's arbiter or system is shutting down
+    Shutdown,
+    /// Actor stopped because of an error
+    Error(String),
+    /// Linked actor stopped abnormally, see `Context::link()`
+    Linked(ActorId),
+    /// Supervisor gave up restarting actor, see `GiveUp::Escalate`
+    Escalated,
+    /// Message handler panicked, contains panic message
+    Panicked(String),
+}
+
+impl StopReason {
+    /// Returns `true` if actor stopped normally
+    ///
+    /// `Normal`, `Disconnected` and `Shutdown` reasons are normal.
+    pub fn is_normal(&self) -> bool {
+        match *self {
+            StopReason::Normal | StopReason::Disconnected | StopReason::Shutdown => true,
+            _ => false,
+        }
+    }
+}
+
 /// Actor execution context
 ///
 /// Each actor runs within specific execution context. `Actor::Context` defines
 /// context. Execution context defines type of execution, actor communication channels
 /// (message handling).
+#[allow(unused_variables)]
 pub trait ActorContext: Sized {
 
     /// Immediately stop processing incoming messages and switch to a `stopping` state
     fn stop(&mut self);
 
+    /// Stop actor with specific reason
+    ///
+    /// Reason is delivered to watchers and linked actors.
+    /// Default implementation ignores reason and calls `stop()`.
+    fn stop_with(&mut self, reason: StopReason) {
+        self.stop()
+    }
+
     /// Terminate actor execution
     fn terminate(&mut self);
 
     /// Actor execution state
     fn state(&self) -> ActorState;
+
+    /// Reason of actor stop
+    ///
+    /// Reason is meaningful in `Actor::stopping()`, `Actor::stopped()`
+    /// and `Supervised::restarting()` methods.
+    fn stop_reason(&self) -> StopReason {
+        StopReason::Normal
+    }
+
+    /// Trace context of the message currently being handled
+    ///
+    /// Messages sent from the handler inherit this context,
+    /// see `actix::trace`.
+    fn trace_context(&self) -> Option<TraceContext> {
+        trace::current()
+    }
 }
 
 /// Asynchronous execution context
@@ -372,6 +436,73 @@ pub trait AsyncContext<A>: ActorContext where A: Actor<Context=Self>
     {
         self.spawn(TimerFunc::new(dur, f))
     }
+
+    /// Send message to self periodically, message is created with `f`
+    /// on each tick. Returns spawn handle which could be used for
+    /// cancellation of the whole series.
+    ///
+    /// Missed ticks are skipped, see `notify_interval_with()`.
+    fn notify_interval<M, F>(&mut self, f: F, interval: Duration) -> SpawnHandle
+        where A: Handler<M>, M: Message + 'static, F: FnMut() -> M + 'static
+    {
+        self.notify_interval_with(f, interval, MissedTick::Skip)
+    }
+
+    /// Send message to self periodically with specific missed tick policy.
+    ///
+    /// Panics if `interval` is zero.
+    fn notify_interval_with<M, F>(&mut self, f: F, interval: Duration, policy: MissedTick)
+                                  -> SpawnHandle
+        where A: Handler<M>, M: Message + 'static, F: FnMut() -> M + 'static
+    {
+        assert!(interval > Duration::new(0, 0), "interval must be greater than zero");
+        if self.state() == ActorState::Stopped {
+            error!("Context::notify_interval called for stopped actor.");
+            SpawnHandle::default()
+        } else {
+            self.spawn(ActorIntervalMessageItem::new(f, interval, policy))
+        }
+    }
+
+    /// Execute closure periodically within same Actor and Context.
+    /// Returns spawn handle which could be used for cancellation
+    /// of the whole series. Execution get cancelled if context's
+    /// stop method get called.
+    ///
+    /// Missed ticks are skipped, see `run_interval_with()`.
+    fn run_interval<F>(&mut self, interval: Duration, f: F) -> SpawnHandle
+        where F: FnMut(&mut A, &mut A::Context) + 'static
+    {
+        self.run_interval_with(interval, MissedTick::Skip, f)
+    }
+
+    /// Execute closure periodically with specific missed tick policy.
+    ///
+    /// Panics if `interval` is zero.
+    fn run_interval_with<F>(&mut self, interval: Duration, policy: MissedTick, f: F)
+                            -> SpawnHandle
+        where F: FnMut(&mut A, &mut A::Context) + 'static
+    {
+        assert!(interval > Duration::new(0, 0), "interval must be greater than zero");
+        if self.state() == ActorState::Stopped {
+            error!("Context::run_interval called for stopped actor.");
+            SpawnHandle::default()
+        } else {
+            self.spawn(IntervalFunc::new(interval, policy, f))
+        }
+    }
+}
+
+/// What periodic timer does if it could not keep up with its schedule,
+/// i.e. because actor was busy or waiting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MissedTick {
+    /// Drop missed ticks, next tick stays aligned to the original schedule
+    Skip,
+    /// Fire all missed ticks as soon as possible
+    Burst,
+    /// Schedule next tick one interval after late tick
+    Delay,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -400,3 +531,20 @@ impl Default for SpawnHandle {
         SpawnHandle(0)
     }
 }
+
+/// Unique actor identifier
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Copy, Clone, Hash)]
+pub struct ActorId(usize);
+
+impl ActorId {
+    /// Generate new unique id
+    pub(crate) fn next() -> ActorId {
+        static COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+        ActorId(COUNTER.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    #[doc(hidden)]
+    pub fn into_usize(self) -> usize {
+        self.0
+    }
+}
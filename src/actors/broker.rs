@@ -0,0 +1,237 @@
+//! Typed publish/subscribe broker
+//!
+//! `Broker` keeps subscribers for any message type `M: Message + Clone`
+//! and delivers published messages to all of them. Broker is available as
+//! system service, `Broker<SystemBroker>`, and as arbiter service,
+//! `Broker<ArbiterBroker>`. Subscribers with closed mailbox get removed
+//! automatically.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate actix;
+//! use actix::prelude::*;
+//! use actix::actors::broker::{Broker, Subscribe, Publish};
+//!
+//! #[derive(Message, Clone)]
+//! struct OrderCreated(usize);
+//!
+//! struct Listener;
+//!
+//! impl Actor for Listener {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<OrderCreated> for Listener {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, msg: OrderCreated, _: &mut Self::Context) {
+//!         println!("order created: {}", msg.0);
+//! #       Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let addr: Addr<Syn, _> = Listener.start();
+//!     let broker = Arbiter::system_registry().get::<Broker>();
+//!     broker.do_send(Subscribe(addr.recipient()));
+//!     broker.do_send(Publish(OrderCreated(1)));
+//!
+//!     sys.run();
+//! }
+//! ```
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use futures::Future;
+use futures::future::join_all;
+
+use prelude::*;
+
+
+/// Marker for broker registered as `SystemService`
+pub struct SystemBroker;
+
+/// Marker for broker registered as `ArbiterService`
+pub struct ArbiterBroker;
+
+/// Subscription identifier, returned by `Subscribe` message
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(pub(crate) usize);
+
+/// Publish/subscribe broker
+pub struct Broker<T=SystemBroker> {
+    subscribers: HashMap<TypeId, Box<Any>>,
+    next_id: usize,
+    kind: PhantomData<T>,
+}
+
+type Subscribers<M> = Vec<(SubscriptionId, Recipient<Syn, M>)>;
+
+impl<T> Default for Broker<T> {
+    fn default() -> Self {
+        Broker{subscribers: HashMap::new(), next_id: 0, kind: PhantomData}
+    }
+}
+
+impl<T: 'static> Actor for Broker<T> {
+    type Context = Context<Self>;
+}
+
+impl<T: 'static> Supervised for Broker<T> {}
+
+impl SystemService for Broker<SystemBroker> {}
+
+impl ArbiterService for Broker<ArbiterBroker> {}
+
+impl<T> Broker<T> {
+    fn subscribers<M>(&mut self) -> &mut Subscribers<M>
+        where M: Message + Send + 'static, M::Result: Send
+    {
+        self.subscribers.entry(TypeId::of::<M>())
+            .or_insert_with(|| Box::new(Subscribers::<M>::new()))
+            .downcast_mut::<Subscribers<M>>()
+            .expect("Broker subscribers are keyed by message type")
+    }
+
+    // Deliver message to all subscribers except `skip`, drop closed subscribers
+    fn issue<M>(&mut self, msg: M, skip: Option<SubscriptionId>)
+        where M: Message + Clone + Send + 'static, M::Result: Send
+    {
+        self.subscribers::<M>().retain(|&(id, ref rcp)| {
+            if Some(id) == skip {
+                return true
+            }
+            match rcp.do_send(msg.clone()) {
+                Err(SendError::Closed(_)) => false,
+                _ => true,
+            }
+        });
+    }
+}
+
+/// Subscribe recipient to messages of type `M`
+pub struct Subscribe<M>(pub Recipient<Syn, M>)
+    where M: Message + Send + 'static, M::Result: Send;
+
+impl<M> Message for Subscribe<M> where M: Message + Send + 'static, M::Result: Send {
+    type Result = SubscriptionId;
+}
+
+impl<T, M> Handler<Subscribe<M>> for Broker<T>
+    where T: 'static, M: Message + Send + 'static, M::Result: Send
+{
+    type Result = MessageResult<Subscribe<M>>;
+
+    fn handle(&mut self, msg: Subscribe<M>, _: &mut Self::Context) -> Self::Result {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.subscribers::<M>().push((id, msg.0));
+        MessageResult(id)
+    }
+}
+
+/// Remove subscription for messages of type `M`
+pub struct Unsubscribe<M> {
+    id: SubscriptionId,
+    msg: PhantomData<M>,
+}
+
+impl<M> Unsubscribe<M> {
+    /// Create unsubscribe message for subscription `id` returned by `Subscribe<M>`
+    pub fn new(id: SubscriptionId) -> Self {
+        Unsubscribe{id: id, msg: PhantomData}
+    }
+}
+
+impl<M: 'static> Message for Unsubscribe<M> {
+    type Result = ();
+}
+
+impl<T, M> Handler<Unsubscribe<M>> for Broker<T>
+    where T: 'static, M: Message + Send + 'static, M::Result: Send
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: Unsubscribe<M>, _: &mut Self::Context) {
+        self.subscribers::<M>().retain(|&(id, _)| id != msg.id);
+    }
+}
+
+/// Deliver message to all subscribers
+pub struct Publish<M>(pub M);
+
+impl<M: 'static> Message for Publish<M> {
+    type Result = ();
+}
+
+impl<T, M> Handler<Publish<M>> for Broker<T>
+    where T: 'static, M: Message + Clone + Send + 'static, M::Result: Send
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: Publish<M>, _: &mut Self::Context) {
+        self.issue(msg.0, None)
+    }
+}
+
+/// Deliver message to all subscribers except issuer's own subscription
+/// without waiting for results.
+pub struct IssueAsync<M>(pub M, pub SubscriptionId);
+
+impl<M: 'static> Message for IssueAsync<M> {
+    type Result = ();
+}
+
+impl<T, M> Handler<IssueAsync<M>> for Broker<T>
+    where T: 'static, M: Message + Clone + Send + 'static, M::Result: Send
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: IssueAsync<M>, _: &mut Self::Context) {
+        self.issue(msg.0, Some(msg.1))
+    }
+}
+
+/// Deliver message to all subscribers except issuer's own subscription
+/// and wait until all of them handle it.
+///
+/// Result contains responses of subscribers that handled the message.
+pub struct IssueSync<M>(pub M, pub SubscriptionId);
+
+impl<M: Message + 'static> Message for IssueSync<M> {
+    type Result = Result<Vec<M::Result>, ()>;
+}
+
+impl<T, M> Handler<IssueSync<M>> for Broker<T>
+    where T: 'static, M: Message + Clone + Send + 'static, M::Result: Send
+{
+    type Result = ActorResponse<Self, Vec<M::Result>, ()>;
+
+    fn handle(&mut self, msg: IssueSync<M>, _: &mut Self::Context) -> Self::Result {
+        let IssueSync(msg, skip) = msg;
+        let requests: Vec<_> = self.subscribers::<M>().iter()
+            .filter(|&&(id, _)| id != skip)
+            .map(|&(id, ref rcp)| rcp.send(msg.clone()).then(move |res| Ok::<_, ()>((id, res))))
+            .collect();
+
+        ActorResponse::async(
+            join_all(requests)
+                .into_actor(self)
+                .map(|results, act, _| {
+                    let mut items = Vec::new();
+                    for (id, res) in results {
+                        match res {
+                            Ok(item) => items.push(item),
+                            Err(MailboxError::Closed) =>
+                                act.subscribers::<M>().retain(|&(i, _)| i != id),
+                            Err(MailboxError::Timeout) | Err(MailboxError::Panicked) => (),
+                        }
+                    }
+                    items
+                }))
+    }
+}
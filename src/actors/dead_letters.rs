@@ -0,0 +1,145 @@
+//! Dead letters office
+//!
+//! `DeadLetters` is a system service that receives a description of every
+//! message that could not be delivered to an actor: actor's mailbox is closed,
+//! message got rejected or dropped by mailbox overflow policy or request
+//! timed out. Reporting is enabled only while service is running, start it
+//! with `DeadLetters::from_registry()` and subscribe for dead letters
+//! with `Subscribe` message.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # extern crate actix;
+//! use actix::prelude::*;
+//! use actix::msgs::DeadLetter;
+//! use actix::actors::dead_letters::{DeadLetters, Subscribe};
+//!
+//! struct Monitor;
+//!
+//! impl Actor for Monitor {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<DeadLetter> for Monitor {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, msg: DeadLetter, _: &mut Self::Context) {
+//!         println!("{} -> {}: {:?}", msg.message, msg.actor, msg.reason);
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let monitor: Addr<Syn, _> = Monitor.start();
+//!     let dead_letters: Addr<Syn, _> = DeadLetters::from_registry();
+//!     dead_letters.do_send(Subscribe(monitor.recipient()));
+//!
+//! #   Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     sys.run();
+//! }
+//! ```
+use prelude::*;
+use msgs::{DeadLetter, DeadLetterReason};
+use utils::type_name;
+
+
+/// Dead letters office
+pub struct DeadLetters {
+    subscribers: Vec<Recipient<Syn, DeadLetter>>,
+    count: usize,
+}
+
+impl Default for DeadLetters {
+    fn default() -> Self {
+        DeadLetters{subscribers: Vec::new(), count: 0}
+    }
+}
+
+impl Actor for DeadLetters {
+    type Context = Context<Self>;
+}
+
+impl Supervised for DeadLetters {}
+
+impl SystemService for DeadLetters {}
+
+impl Handler<DeadLetter> for DeadLetters {
+    type Result = ();
+
+    fn handle(&mut self, msg: DeadLetter, _: &mut Self::Context) {
+        self.count += 1;
+        debug!("Dead letter: {} to {} ({:?})", msg.message, msg.actor, msg.reason);
+
+        let subscribers = ::std::mem::replace(&mut self.subscribers, Vec::new());
+        for subscr in subscribers {
+            if subscr.do_send(msg.clone()).is_ok() {
+                self.subscribers.push(subscr);
+            }
+        }
+    }
+}
+
+/// Subscribe to dead letters.
+pub struct Subscribe(pub Recipient<Syn, DeadLetter>);
+
+impl Message for Subscribe {
+    type Result = ();
+}
+
+impl Handler<Subscribe> for DeadLetters {
+    type Result = ();
+
+    fn handle(&mut self, msg: Subscribe, _: &mut Self::Context) {
+        self.subscribers.push(msg.0);
+    }
+}
+
+/// Get number of dead letters received by the service.
+pub struct Count;
+
+impl Message for Count {
+    type Result = usize;
+}
+
+impl Handler<Count> for DeadLetters {
+    type Result = usize;
+
+    fn handle(&mut self, _: Count, _: &mut Self::Context) -> usize {
+        self.count
+    }
+}
+
+/// Report message `M` that could not be delivered to actor `A`.
+pub(crate) fn dead_letter<A: 'static, M: 'static>(reason: DeadLetterReason) {
+    report(type_name::<A>(), type_name::<M>(), reason)
+}
+
+/// Report dead letter to `DeadLetters` service.
+///
+/// Report is silently dropped if service is not running or
+/// current thread does not belong to the system.
+pub(crate) fn report(actor: &'static str, message: &'static str, reason: DeadLetterReason) {
+    report_letter(DeadLetter{actor: actor, message: message, reason: reason, topic: None})
+}
+
+/// Report event with `topic` that could not be delivered to actor.
+pub(crate) fn report_event(actor: &'static str, message: &'static str,
+                           topic: &str, reason: DeadLetterReason) {
+    report_letter(DeadLetter{actor: actor, message: message,
+                             reason: reason, topic: Some(topic.to_owned())})
+}
+
+fn report_letter(letter: DeadLetter) {
+    // failures of dead letters delivery itself are not reported
+    if letter.actor == type_name::<DeadLetters>() || letter.message == type_name::<DeadLetter>() {
+        return
+    }
+
+    if let Some(reg) = Arbiter::try_system_registry() {
+        if let Some(addr) = reg.query::<DeadLetters>() {
+            let _ = addr.do_send(letter);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Topic based event bus
+//!
+//! `EventBus` delivers `Event` messages to subscribers by topic. Topic is a
+//! dot separated string, i.e. `orders.42.created`. Subscription pattern
+//! could contain wildcards, `*` matches exactly one segment and `#` matches
+//! zero or more segments, i.e. `orders.*.created` or `orders.#`.
+//!
+//! Events get delivered with `Recipient::try_send()`, bus never blocks on
+//! slow subscriber. If subscriber's mailbox is full, event is stored in
+//! subscriber's buffer, if buffer is full as well `SubscriberPolicy` is
+//! applied.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # extern crate actix;
+//! use actix::prelude::*;
+//! use actix::actors::event_bus::{Event, EventBus, Publish, Subscribe};
+//!
+//! struct Orders;
+//!
+//! impl Actor for Orders {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<Event> for Orders {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, ev: Event, _: &mut Self::Context) {
+//!         println!("{}: {:?}", ev.topic(), ev.payload::<u64>());
+//! #       Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let addr: Addr<Syn, _> = Orders.start();
+//!     let bus = EventBus::from_registry();
+//!     bus.do_send(Subscribe::new("orders.*.created", addr.recipient()));
+//!     bus.do_send(Publish(Event::new("orders.42.created", 42u64)));
+//!
+//!     sys.run();
+//! }
+//! ```
+use std::mem;
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use futures::{Async, Poll};
+
+use prelude::*;
+use actors::broker::SubscriptionId;
+use actors::dead_letters::report_event;
+use msgs::DeadLetterReason;
+use utils::type_name;
+
+
+/// Event with topic and arbitrary payload
+#[derive(Clone)]
+pub struct Event {
+    topic: String,
+    payload: Arc<Any + Send + Sync>,
+}
+
+impl Event {
+    /// Create new event
+    ///
+    /// Topic is a dot separated string of segments, i.e. `orders.42.created`,
+    /// it should not contain wildcards, those are meaningful only in
+    /// subscription patterns. Payload is shared by all subscribers,
+    /// subscriber gets it with `Event::payload::<P>()`, which returns
+    /// `None` if requested type is not the type of the payload.
+    pub fn new<T, P>(topic: T, payload: P) -> Event
+        where T: Into<String>, P: Any + Send + Sync
+    {
+        Event{topic: topic.into(), payload: Arc::new(payload)}
+    }
+
+    /// Event's topic
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Get reference to the payload if it is of type `T`
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref::<T>()
+    }
+}
+
+impl Message for Event {
+    type Result = ();
+}
+
+/// What to do with an event if subscriber's buffer is full
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubscriberPolicy {
+    /// Drop new event
+    DropNewest,
+    /// Drop oldest buffered event
+    DropOldest,
+    /// Remove subscriber
+    Unsubscribe,
+}
+
+struct Subscriber {
+    id: SubscriptionId,
+    pattern: Vec<String>,
+    recipient: Recipient<Syn, Event>,
+    capacity: usize,
+    policy: SubscriberPolicy,
+    buffer: VecDeque<Event>,
+}
+
+impl Subscriber {
+    // Deliver event, returns false if subscriber has to be removed
+    fn deliver(&mut self, ev: Event) -> bool {
+        if !self.flush() {
+            return false
+        }
+        if !self.buffer.is_empty() {
+            return self.enqueue(ev)
+        }
+        match self.recipient.try_send(ev) {
+            Ok(()) => true,
+            Err(SendError::Full(ev)) => self.enqueue(ev),
+            Err(SendError::Closed(_)) => false,
+        }
+    }
+
+    // Send buffered events, returns false if subscriber has to be removed
+    fn flush(&mut self) -> bool {
+        while let Some(ev) = self.buffer.pop_front() {
+            match self.recipient.try_send(ev) {
+                Ok(()) => (),
+                Err(SendError::Full(ev)) => {
+                    self.buffer.push_front(ev);
+                    return true
+                }
+                Err(SendError::Closed(_)) => return false,
+            }
+        }
+        true
+    }
+
+    fn enqueue(&mut self, ev: Event) -> bool {
+        if self.buffer.len() < self.capacity {
+            self.buffer.push_back(ev);
+            return true
+        }
+        let dropped = match self.policy {
+            SubscriberPolicy::DropNewest => ev,
+            SubscriberPolicy::DropOldest => match self.buffer.pop_front() {
+                Some(oldest) => {
+                    self.buffer.push_back(ev);
+                    oldest
+                }
+                None => ev,
+            },
+            SubscriberPolicy::Unsubscribe => return false,
+        };
+        // recipient created without address does not know its actor
+        let actor = self.recipient.actor().unwrap_or_else(type_name::<EventBus>);
+        report_event(actor, type_name::<Event>(), &dropped.topic, DeadLetterReason::Dropped);
+        true
+    }
+}
+
+fn matches(pattern: &[String], topic: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => topic.is_empty(),
+        Some((p, rest)) if p == "#" =>
+            (0..topic.len()+1).any(|idx| matches(rest, &topic[idx..])),
+        Some((p, rest)) => match topic.split_first() {
+            Some((t, topic)) => (p == "*" || p == t) && matches(rest, topic),
+            None => false,
+        },
+    }
+}
+
+/// Topic based event bus
+pub struct EventBus {
+    subscribers: Vec<Subscriber>,
+    next_id: usize,
+    flushing: bool,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus{subscribers: Vec::new(), next_id: 0, flushing: false}
+    }
+}
+
+impl Actor for EventBus {
+    type Context = Context<Self>;
+}
+
+impl Supervised for EventBus {}
+
+impl SystemService for EventBus {}
+
+impl EventBus {
+    // Send buffered events, until all buffers are empty
+    fn flush(&mut self, ctx: &mut Context<Self>) {
+        if !self.flushing && self.subscribers.iter().any(|s| !s.buffer.is_empty()) {
+            self.flushing = true;
+            ctx.spawn(Flush);
+        }
+    }
+}
+
+/// Subscribe to events with topic matching pattern
+pub struct Subscribe {
+    pattern: String,
+    recipient: Recipient<Syn, Event>,
+    capacity: usize,
+    policy: SubscriberPolicy,
+}
+
+impl Subscribe {
+    /// Subscribe without buffering, event get dropped if
+    /// subscriber's mailbox is full.
+    pub fn new<T: Into<String>>(pattern: T, recipient: Recipient<Syn, Event>) -> Subscribe {
+        Subscribe{pattern: pattern.into(),
+                  recipient: recipient,
+                  capacity: 0,
+                  policy: SubscriberPolicy::DropNewest}
+    }
+
+    /// Buffer up to `capacity` events while subscriber's mailbox is full
+    pub fn buffer(mut self, capacity: usize, policy: SubscriberPolicy) -> Subscribe {
+        self.capacity = capacity;
+        self.policy = policy;
+        self
+    }
+}
+
+impl Message for Subscribe {
+    type Result = SubscriptionId;
+}
+
+impl Handler<Subscribe> for EventBus {
+    type Result = MessageResult<Subscribe>;
+
+    fn handle(&mut self, msg: Subscribe, _: &mut Self::Context) -> Self::Result {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.push(Subscriber {
+            id: id,
+            pattern: msg.pattern.split('.').map(|s| s.to_owned()).collect(),
+            recipient: msg.recipient,
+            capacity: msg.capacity,
+            policy: msg.policy,
+            buffer: VecDeque::new(),
+        });
+        MessageResult(id)
+    }
+}
+
+/// Remove subscription
+pub struct Unsubscribe(pub SubscriptionId);
+
+impl Message for Unsubscribe {
+    type Result = ();
+}
+
+impl Handler<Unsubscribe> for EventBus {
+    type Result = ();
+
+    fn handle(&mut self, msg: Unsubscribe, _: &mut Self::Context) {
+        self.subscribers.retain(|s| s.id != msg.0);
+    }
+}
+
+/// Publish event to all subscribers with matching pattern
+pub struct Publish(pub Event);
+
+impl Message for Publish {
+    type Result = ();
+}
+
+impl Handler<Publish> for EventBus {
+    type Result = ();
+
+    fn handle(&mut self, msg: Publish, ctx: &mut Self::Context) {
+        let ev = msg.0;
+        let topic: Vec<&str> = ev.topic.split('.').collect();
+        let subscribers = mem::replace(&mut self.subscribers, Vec::new());
+        for mut subscr in subscribers {
+            if !matches(&subscr.pattern, &topic) || subscr.deliver(ev.clone()) {
+                self.subscribers.push(subscr);
+            }
+        }
+        self.flush(ctx);
+    }
+}
+
+// Retry buffered events when subscribers' mailboxes get free space
+struct Flush;
+
+impl ActorFuture for Flush {
+    type Item = ();
+    type Error = ();
+    type Actor = EventBus;
+
+    fn poll(&mut self, act: &mut EventBus, _: &mut Context<EventBus>) -> Poll<(), ()> {
+        let subscribers = mem::replace(&mut act.subscribers, Vec::new());
+        for mut subscr in subscribers {
+            if subscr.flush() {
+                act.subscribers.push(subscr);
+            }
+        }
+        if act.subscribers.iter().any(|s| !s.buffer.is_empty()) {
+            Ok(Async::NotReady)
+        } else {
+            act.flushing = false;
+            Ok(Async::Ready(()))
+        }
+    }
+}
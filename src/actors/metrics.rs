@@ -0,0 +1,395 @@
+//! Prometheus metrics exporter
+//!
+//! `MetricsExporter` actor serves `GET /metrics` in Prometheus text
+//! exposition format. Exporter reports user defined counters and gauges
+//! registered with `Register` message and, if `metrics` feature is enabled,
+//! runtime metrics of actors of the system aggregated by arbiter and
+//! actor type, see `actix::metrics`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # extern crate actix;
+//! use actix::prelude::*;
+//! use actix::actors::metrics::{Counter, MetricsExporter, Register};
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let requests = Counter::new("requests_total", "Number of handled requests");
+//!     let exporter: Addr<Syn, _> =
+//!         MetricsExporter::bind(&"127.0.0.1:0".parse().unwrap()).unwrap().start();
+//!     exporter.do_send(Register::Counter(requests.clone()));
+//!
+//!     // in request handler
+//!     requests.inc();
+//!
+//! #   Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     sys.run();
+//! }
+//! ```
+use std::{fmt, io};
+use std::io::Read;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+use futures::{Async, Future, Poll};
+use tokio_core::net::{TcpListener, TcpStream};
+use tokio_io::io::write_all;
+
+use prelude::*;
+#[cfg(feature="metrics")]
+use std::collections::btree_map::Entry;
+#[cfg(feature="metrics")]
+use std::time::Duration;
+#[cfg(feature="metrics")]
+use metrics::{self, HandlerMetrics, Histogram};
+
+
+/// Maximum size of request head
+const MAX_HEAD: usize = 8192;
+
+/// Monotonically increasing counter
+#[derive(Clone)]
+pub struct Counter(Arc<MetricInner<AtomicUsize>>);
+
+/// Gauge, value that can go up and down
+#[derive(Clone)]
+pub struct Gauge(Arc<MetricInner<AtomicIsize>>);
+
+struct MetricInner<T> {
+    name: String,
+    help: String,
+    value: T,
+}
+
+impl Counter {
+    /// Create new counter, `name` has to be valid Prometheus metric name
+    pub fn new<N: Into<String>, H: Into<String>>(name: N, help: H) -> Counter {
+        Counter(Arc::new(MetricInner{
+            name: name.into(), help: help.into(), value: AtomicUsize::new(0)}))
+    }
+
+    /// Increment counter by one
+    pub fn inc(&self) {
+        self.add(1)
+    }
+
+    /// Increment counter by `n`
+    pub fn add(&self, n: usize) {
+        self.0.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Current value
+    pub fn get(&self) -> usize {
+        self.0.value.load(Ordering::Relaxed)
+    }
+}
+
+impl Gauge {
+    /// Create new gauge, `name` has to be valid Prometheus metric name
+    pub fn new<N: Into<String>, H: Into<String>>(name: N, help: H) -> Gauge {
+        Gauge(Arc::new(MetricInner{
+            name: name.into(), help: help.into(), value: AtomicIsize::new(0)}))
+    }
+
+    /// Set gauge to `value`
+    pub fn set(&self, value: isize) {
+        self.0.value.store(value, Ordering::Relaxed);
+    }
+
+    /// Increment gauge by one
+    pub fn inc(&self) {
+        self.add(1)
+    }
+
+    /// Decrement gauge by one
+    pub fn dec(&self) {
+        self.add(-1)
+    }
+
+    /// Add `n` to gauge
+    pub fn add(&self, n: isize) {
+        self.0.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Current value
+    pub fn get(&self) -> isize {
+        self.0.value.load(Ordering::Relaxed)
+    }
+}
+
+/// Register user metric, metric with same name gets replaced
+pub enum Register {
+    /// Register counter
+    Counter(Counter),
+    /// Register gauge
+    Gauge(Gauge),
+}
+
+impl Message for Register {
+    type Result = ();
+}
+
+/// Prometheus metrics exporter
+pub struct MetricsExporter {
+    listener: Option<TcpListener>,
+    addr: SocketAddr,
+    metrics: BTreeMap<String, Register>,
+}
+
+impl MetricsExporter {
+    /// Bind exporter's listener to `addr` in current arbiter
+    pub fn bind(addr: &SocketAddr) -> io::Result<MetricsExporter> {
+        let listener = TcpListener::bind(addr, Arbiter::handle())?;
+        Ok(MetricsExporter {
+            addr: listener.local_addr()?,
+            listener: Some(listener),
+            metrics: BTreeMap::new(),
+        })
+    }
+
+    /// Address exporter listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Render all metrics in Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for metric in self.metrics.values() {
+            match *metric {
+                Register::Counter(ref c) => {
+                    family(&mut out, &c.0.name, "counter", &c.0.help);
+                    sample(&mut out, &c.0.name, &[], c.get());
+                }
+                Register::Gauge(ref g) => {
+                    family(&mut out, &g.0.name, "gauge", &g.0.help);
+                    sample(&mut out, &g.0.name, &[], g.get());
+                }
+            }
+        }
+        render_actors(&mut out);
+        out
+    }
+
+    fn response(&self, path: &str) -> Vec<u8> {
+        let (status, body) = if path == "/metrics" {
+            ("200 OK", self.render())
+        } else {
+            ("404 Not Found", "Not Found\n".to_owned())
+        };
+        format!("HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, body.len(), body).into_bytes()
+    }
+}
+
+impl Actor for MetricsExporter {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        if let Some(listener) = self.listener.take() {
+            ctx.add_stream(listener.incoming());
+        }
+    }
+}
+
+impl Handler<Register> for MetricsExporter {
+    type Result = ();
+
+    fn handle(&mut self, msg: Register, _: &mut Self::Context) {
+        let name = match msg {
+            Register::Counter(ref c) => c.0.name.clone(),
+            Register::Gauge(ref g) => g.0.name.clone(),
+        };
+        self.metrics.insert(name, msg);
+    }
+}
+
+impl StreamHandler<(TcpStream, SocketAddr), io::Error> for MetricsExporter {
+
+    fn handle(&mut self, (stream, _): (TcpStream, SocketAddr), ctx: &mut Self::Context) {
+        ReadHead{stream: Some(stream), buf: Vec::new()}
+            .into_actor(self)
+            .and_then(|(stream, path), act, _| {
+                write_all(stream, act.response(&path)).map(|_| ()).into_actor(act)
+            })
+            .map_err(|err, _, _| debug!("Metrics exporter connection error: {}", err))
+            .spawn(ctx);
+    }
+
+    fn error(&mut self, err: io::Error, _: &mut Self::Context) -> ErrorAction {
+        warn!("Metrics exporter accept error: {}", err);
+        ErrorAction::Continue
+    }
+}
+
+/// Read http request head, resolves with request path
+struct ReadHead {
+    stream: Option<TcpStream>,
+    buf: Vec<u8>,
+}
+
+impl Future for ReadHead {
+    type Item = (TcpStream, String);
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let mut chunk = [0u8; 1024];
+        loop {
+            let n = match self.stream.as_mut().unwrap().read(&mut chunk) {
+                Ok(0) => return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof, "Connection closed")),
+                Ok(n) => n,
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock =>
+                    return Ok(Async::NotReady),
+                Err(err) => return Err(err),
+            };
+            self.buf.extend_from_slice(&chunk[..n]);
+
+            if self.buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                let path = {
+                    let head = String::from_utf8_lossy(&self.buf);
+                    let mut parts = head.split_whitespace();
+                    match (parts.next(), parts.next()) {
+                        (Some("GET"), Some(path)) => path.split('?').next().unwrap().to_owned(),
+                        _ => String::new(),
+                    }
+                };
+                return Ok(Async::Ready((self.stream.take().unwrap(), path)))
+            }
+            if self.buf.len() > MAX_HEAD {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Request is too large"))
+            }
+        }
+    }
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+    let _ = write!(out, "# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind);
+}
+
+fn sample<V: fmt::Display>(out: &mut String, name: &str, labels: &[(&str, &str)], value: V) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        out.push('{');
+        for (idx, &(label, value)) in labels.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            let _ = write!(out, "{}=\"{}\"", label, value);
+        }
+        out.push('}');
+    }
+    let _ = write!(out, " {}\n", value);
+}
+
+#[cfg(not(feature="metrics"))]
+fn render_actors(_: &mut String) {}
+
+#[cfg(feature="metrics")]
+#[derive(Default)]
+struct Aggregate {
+    actors: usize,
+    received: u64,
+    handled: u64,
+    sync_queue: usize,
+    unsync_queue: usize,
+    spawned: u64,
+    restarts: u64,
+}
+
+#[cfg(feature="metrics")]
+fn seconds(dur: Duration) -> f64 {
+    dur.as_secs() as f64 + f64::from(dur.subsec_nanos()) / 1_000_000_000.0
+}
+
+#[cfg(feature="metrics")]
+fn aggregate(out: &mut String, actors: &BTreeMap<(String, &'static str), Aggregate>,
+             name: &str, kind: &str, help: &str, f: fn(&Aggregate) -> u64) {
+    family(out, name, kind, help);
+    for (&(ref arbiter, actor), agg) in actors {
+        sample(out, name, &[("arbiter", arbiter), ("actor", actor)], f(agg));
+    }
+}
+
+#[cfg(feature="metrics")]
+fn add_handlers(handlers: &mut BTreeMap<(String, &'static str, &'static str), Histogram>,
+                arbiter: &str, actor: &'static str, metrics: Vec<HandlerMetrics>) {
+    for h in metrics {
+        match handlers.entry((arbiter.to_owned(), actor, h.message)) {
+            Entry::Vacant(entry) => {
+                entry.insert(h.time);
+            }
+            Entry::Occupied(mut entry) => entry.get_mut().merge(&h.time),
+        }
+    }
+}
+
+// Runtime metrics of actors aggregated by arbiter and actor type
+#[cfg(feature="metrics")]
+fn render_actors(out: &mut String) {
+    let mut actors: BTreeMap<(String, &'static str), Aggregate> = BTreeMap::new();
+    let mut handlers: BTreeMap<(String, &'static str, &'static str), Histogram> = BTreeMap::new();
+
+    for m in metrics::snapshot() {
+        {
+            let agg = actors.entry((m.arbiter.clone(), m.actor)).or_insert_with(Aggregate::default);
+            agg.actors += 1;
+            agg.received += m.received;
+            agg.handled += m.handled;
+            agg.sync_queue += m.sync_queue;
+            agg.unsync_queue += m.unsync_queue;
+            agg.spawned += m.spawned;
+            agg.restarts += m.restarts;
+        }
+        add_handlers(&mut handlers, &m.arbiter, m.actor, m.handlers);
+    }
+
+    // counters of stopped actors, so totals never decrease
+    for m in metrics::stopped() {
+        {
+            let agg = actors.entry((m.arbiter.clone(), m.actor)).or_insert_with(Aggregate::default);
+            agg.received += m.received;
+            agg.handled += m.handled;
+            agg.spawned += m.spawned;
+            agg.restarts += m.restarts;
+        }
+        add_handlers(&mut handlers, &m.arbiter, m.actor, m.handlers);
+    }
+
+    aggregate(out, &actors, "actix_actors", "gauge",
+              "Number of running actors", |a| a.actors as u64);
+    aggregate(out, &actors, "actix_mailbox_sync_depth", "gauge",
+              "Messages in sync queues of actors' mailboxes", |a| a.sync_queue as u64);
+    aggregate(out, &actors, "actix_mailbox_unsync_depth", "gauge",
+              "Messages in unsync queues of actors' mailboxes", |a| a.unsync_queue as u64);
+    aggregate(out, &actors, "actix_messages_received_total", "counter",
+              "Messages taken from actors' mailboxes", |a| a.received);
+    aggregate(out, &actors, "actix_messages_handled_total", "counter",
+              "Messages handled without panic", |a| a.handled);
+    aggregate(out, &actors, "actix_spawned_futures_total", "counter",
+              "Futures spawned in actors' contexts", |a| a.spawned);
+    aggregate(out, &actors, "actix_restarts_total", "counter",
+              "Actor restarts", |a| a.restarts);
+
+    family(out, "actix_handler_seconds", "histogram", "Message handler time");
+    for (&(ref arbiter, actor, message), hist) in &handlers {
+        let labels = [("arbiter", arbiter.as_str()), ("actor", actor), ("message", message)];
+        for &(bound, count) in &hist.buckets {
+            let le = seconds(bound).to_string();
+            sample(out, "actix_handler_seconds_bucket",
+                   &[labels[0], labels[1], labels[2], ("le", le.as_str())], count);
+        }
+        sample(out, "actix_handler_seconds_bucket",
+               &[labels[0], labels[1], labels[2], ("le", "+Inf")], hist.count);
+        sample(out, "actix_handler_seconds_sum", &labels, seconds(hist.sum));
+        sample(out, "actix_handler_seconds_count", &labels, hist.count);
+    }
+}
@@ -2,5 +2,12 @@
 
 mod resolver;
 pub mod signal;
+pub mod dead_letters;
+pub mod broker;
+pub mod event_bus;
+pub mod supervisor_tree;
+pub mod scheduler;
+pub mod metrics;
 
 pub use self::resolver::{Connect, ConnectAddr, Resolve, Connector, ConnectorError};
+pub use self::broker::{Broker, SystemBroker, ArbiterBroker};
@@ -0,0 +1,492 @@
+//! Cron-style scheduler
+//!
+//! `Scheduler` is a system service that delivers messages at wall-clock
+//! times matching cron expression. Expression has six fields, seconds,
+//! minutes, hours, day of month, month and day of week, i.e.
+//! `0 */5 * * * *` matches every five minutes. Five fields expression
+//! without seconds is accepted as well. Each field is `*`, a number, a range
+//! `a-b` or a list `a,b,c`, optionally with step `*/n` or `a-b/n`. Day of
+//! week is `0-7`, both `0` and `7` are Sunday.
+//!
+//! Times are computed in UTC shifted by schedule's fixed offset, see
+//! `Schedule::offset()`. If process was suspended and scheduled times
+//! got missed, `CatchUp` policy is applied.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # extern crate actix;
+//! use actix::prelude::*;
+//! use actix::actors::scheduler::{Schedule, Scheduler};
+//!
+//! struct Compact;
+//!
+//! impl Message for Compact {
+//!     type Result = ();
+//! }
+//!
+//! struct Storage;
+//!
+//! impl Actor for Storage {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<Compact> for Storage {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, _: Compact, _: &mut Self::Context) {
+//!         println!("compacting");
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let addr: Addr<Syn, _> = Storage.start();
+//!     let scheduler = Scheduler::from_registry();
+//!     // every night at 03:00 UTC+1
+//!     scheduler.do_send(
+//!         Schedule::new("0 0 3 * * *", addr.recipient(), || Compact).offset(3600));
+//!
+//! #   Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     sys.run();
+//! }
+//! ```
+use std::{cmp, fmt, mem};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use prelude::*;
+
+
+/// Max number of steps while searching for next matching time
+const MAX_STEPS: usize = 100_000;
+
+/// Max number of missed times delivered by `CatchUp::All`
+const MAX_CATCH_UP: usize = 1000;
+
+/// Max time scheduler sleeps before checking wall clock again, monotonic
+/// timers do not advance while process is suspended.
+const MAX_SLEEP: u64 = 60;
+
+/// Delivery is considered late if it is delayed by more than `GRACE` seconds
+const GRACE: i64 = 1;
+
+/// Error parsing cron expression
+#[derive(Fail, Debug, PartialEq)]
+pub enum CronError {
+    /// Expression does not have 5 or 6 fields
+    #[fail(display = "Cron expression has to have 5 or 6 fields")]
+    Fields,
+
+    /// Invalid field value
+    #[fail(display = "Invalid cron field: {}", _0)]
+    Field(String),
+
+    /// Expression never matches, i.e. `0 0 0 30 2 *`
+    #[fail(display = "Cron expression never matches")]
+    NeverMatches,
+}
+
+/// Parsed cron expression
+#[derive(Clone, PartialEq)]
+pub struct CronExpr {
+    source: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    /// First matching time after `now`
+    ///
+    /// Times are seconds since unix epoch, `offset` is time zone offset
+    /// in seconds east of UTC.
+    pub fn next_after(&self, now: u64, offset: i32) -> Option<u64> {
+        let offset = i64::from(offset);
+        let mut t = now as i64 + offset + 1;
+
+        for _ in 0..MAX_STEPS {
+            let days = t / 86_400;
+            let secs = t % 86_400;
+            let (y, m, d) = civil_from_days(days);
+
+            if !is_set(self.months, m) {
+                let (y, m) = if m == 12 { (y + 1, 1) } else { (y, m + 1) };
+                t = days_from_civil(y, m, 1) * 86_400;
+                continue
+            }
+            if !self.day_matches(d, weekday(days)) {
+                t = (days + 1) * 86_400;
+                continue
+            }
+            let hour = secs / 3600;
+            if !is_set(self.hours, hour as u32) {
+                t = days * 86_400 + (hour + 1) * 3600;
+                continue
+            }
+            let minute = secs % 3600 / 60;
+            if !is_set(self.minutes, minute as u32) {
+                t = days * 86_400 + hour * 3600 + (minute + 1) * 60;
+                continue
+            }
+            if !is_set(self.seconds, (secs % 60) as u32) {
+                t += 1;
+                continue
+            }
+            let t = t - offset;
+            return if t < 0 { None } else { Some(t as u64) }
+        }
+        None
+    }
+
+    fn day_matches(&self, day: u32, weekday: u32) -> bool {
+        let dom = is_set(self.days, day);
+        let dow = is_set(self.weekdays, weekday);
+
+        // if both fields are restricted, either of them has to match
+        if self.any_day || self.any_weekday {
+            dom && dow
+        } else {
+            dom || dow
+        }
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<CronExpr, CronError> {
+        let mut fields: Vec<&str> = s.split_whitespace().collect();
+        match fields.len() {
+            5 => fields.insert(0, "0"),
+            6 => (),
+            _ => return Err(CronError::Fields),
+        }
+
+        let mut weekdays = parse_field(fields[5], 0, 7)?;
+        if is_set(weekdays, 7) {
+            weekdays |= 1;
+        }
+        Ok(CronExpr {
+            source: s.to_owned(),
+            seconds: parse_field(fields[0], 0, 59)?,
+            minutes: parse_field(fields[1], 0, 59)?,
+            hours: parse_field(fields[2], 0, 23)?,
+            days: parse_field(fields[3], 1, 31)?,
+            months: parse_field(fields[4], 1, 12)?,
+            weekdays: weekdays,
+            any_day: fields[3].starts_with('*'),
+            any_weekday: fields[5].starts_with('*'),
+        })
+    }
+}
+
+impl fmt::Debug for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CronExpr({:?})", self.source)
+    }
+}
+
+fn is_set(bits: u64, val: u32) -> bool {
+    bits & (1 << val) != 0
+}
+
+// Parse field to bit set of matching values
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, CronError> {
+    let invalid = || CronError::Field(field.to_owned());
+    let num = |s: &str| -> Result<u32, CronError> {
+        match s.parse::<u32>() {
+            Ok(val) if val >= min && val <= max => Ok(val),
+            _ => Err(invalid()),
+        }
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let mut split = part.splitn(2, '/');
+        let range = split.next().unwrap_or("");
+        let step = match split.next() {
+            Some(step) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => Some(step),
+                _ => return Err(invalid()),
+            },
+            None => None,
+        };
+
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some(idx) = range.find('-') {
+            (num(&range[..idx])?, num(&range[idx+1..])?)
+        } else {
+            let from = num(range)?;
+            // `a/n` means from `a` to max with step `n`
+            if step.is_some() { (from, max) } else { (from, from) }
+        };
+        if from > to {
+            return Err(invalid())
+        }
+
+        let mut val = from;
+        while val <= to {
+            bits |= 1 << val;
+            val += step.unwrap_or(1);
+        }
+    }
+    Ok(bits)
+}
+
+// Days since unix epoch of a proleptic gregorian date
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = i64::from((m + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Proleptic gregorian date of days since unix epoch
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+// Day of week, 0 is Sunday
+fn weekday(days: i64) -> u32 {
+    // 1970-01-01 is Thursday
+    ((days % 7 + 11) % 7) as u32
+}
+
+fn unix_now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_else(|_| Duration::new(0, 0))
+}
+
+/// What to do with scheduled times missed while process was suspended
+/// or wall clock jumped forward
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CatchUp {
+    /// Drop missed messages
+    Skip,
+    /// Deliver one message for all missed times
+    Once,
+    /// Deliver message for each missed time
+    All,
+}
+
+/// Schedule identifier, returned by `Schedule` message
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScheduleId(usize);
+
+/// Deliver messages created by `message_factory` to `recipient`
+/// at times matching cron expression
+pub struct Schedule<M> where M: Message + Send + 'static, M::Result: Send {
+    cron: String,
+    recipient: Recipient<Syn, M>,
+    message_factory: Box<Fn() -> M + Send>,
+    offset: i32,
+    catch_up: CatchUp,
+}
+
+impl<M> Schedule<M> where M: Message + Send + 'static, M::Result: Send {
+    /// Schedule in UTC, missed messages are delivered once
+    pub fn new<C, F>(cron: C, recipient: Recipient<Syn, M>, message_factory: F) -> Schedule<M>
+        where C: Into<String>, F: Fn() -> M + Send + 'static
+    {
+        Schedule {
+            cron: cron.into(),
+            recipient: recipient,
+            message_factory: Box::new(message_factory),
+            offset: 0,
+            catch_up: CatchUp::Once,
+        }
+    }
+
+    /// Time zone offset in seconds east of UTC, i.e. `3600` for UTC+1
+    pub fn offset(mut self, offset: i32) -> Schedule<M> {
+        self.offset = offset;
+        self
+    }
+
+    /// What to do with missed times
+    pub fn catch_up(mut self, catch_up: CatchUp) -> Schedule<M> {
+        self.catch_up = catch_up;
+        self
+    }
+}
+
+impl<M> Message for Schedule<M> where M: Message + Send + 'static, M::Result: Send {
+    type Result = Result<ScheduleId, CronError>;
+}
+
+/// Cancel schedule
+pub struct Cancel(pub ScheduleId);
+
+impl Message for Cancel {
+    type Result = ();
+}
+
+struct Entry {
+    id: ScheduleId,
+    cron: CronExpr,
+    offset: i32,
+    catch_up: CatchUp,
+    next: u64,
+    deliver: Box<FnMut() -> bool>,
+}
+
+impl Entry {
+    // Deliver due messages, returns false if entry has to be removed
+    fn fire(&mut self, now: u64) -> bool {
+        if self.next > now {
+            return true
+        }
+        let late = now as i64 - self.next as i64;
+
+        let mut due = 0;
+        while self.next <= now {
+            due += 1;
+            let next = if due < MAX_CATCH_UP { self.next } else { now };
+            match self.cron.next_after(next, self.offset) {
+                Some(next) => self.next = next,
+                None => return false,
+            }
+        }
+
+        let count = if late <= GRACE {
+            due
+        } else {
+            warn!("Schedule {:?} missed {} times", self.cron, due);
+            match self.catch_up {
+                CatchUp::Skip => 0,
+                CatchUp::Once => 1,
+                CatchUp::All => due,
+            }
+        };
+        for _ in 0..count {
+            if !(self.deliver)() {
+                return false
+            }
+        }
+        true
+    }
+}
+
+/// Cron-style scheduler
+pub struct Scheduler {
+    entries: Vec<Entry>,
+    next_id: usize,
+    timer: Option<SpawnHandle>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler{entries: Vec::new(), next_id: 0, timer: None}
+    }
+}
+
+impl Actor for Scheduler {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.reschedule(ctx);
+    }
+}
+
+impl Supervised for Scheduler {
+    fn restarting(&mut self, _: &mut Self::Context) {
+        // spawned timer is gone with old context
+        self.timer = None;
+    }
+}
+
+impl SystemService for Scheduler {}
+
+impl Scheduler {
+    // Wake up at earliest scheduled time
+    fn reschedule(&mut self, ctx: &mut Context<Self>) {
+        if let Some(timer) = self.timer.take() {
+            ctx.cancel_future(timer);
+        }
+        if let Some(next) = self.entries.iter().map(|e| e.next).min() {
+            let now = unix_now();
+            let at = Duration::from_secs(next);
+            let dur = if at > now {
+                cmp::min(at - now, Duration::from_secs(MAX_SLEEP))
+            } else {
+                Duration::new(0, 0)
+            };
+            self.timer = Some(ctx.run_later(dur, |act, ctx| {
+                act.timer = None;
+                act.fire(ctx);
+            }));
+        }
+    }
+
+    fn fire(&mut self, ctx: &mut Context<Self>) {
+        let now = unix_now().as_secs();
+        let entries = mem::replace(&mut self.entries, Vec::new());
+        for mut entry in entries {
+            if entry.fire(now) {
+                self.entries.push(entry);
+            }
+        }
+        self.reschedule(ctx);
+    }
+}
+
+impl<M> Handler<Schedule<M>> for Scheduler
+    where M: Message + Send + 'static, M::Result: Send
+{
+    type Result = Result<ScheduleId, CronError>;
+
+    fn handle(&mut self, msg: Schedule<M>, ctx: &mut Self::Context) -> Self::Result {
+        let cron: CronExpr = msg.cron.parse()?;
+        let next = match cron.next_after(unix_now().as_secs(), msg.offset) {
+            Some(next) => next,
+            None => return Err(CronError::NeverMatches),
+        };
+
+        let id = ScheduleId(self.next_id);
+        self.next_id += 1;
+
+        let recipient = msg.recipient;
+        let factory = msg.message_factory;
+        self.entries.push(Entry {
+            id: id,
+            cron: cron,
+            offset: msg.offset,
+            catch_up: msg.catch_up,
+            next: next,
+            deliver: Box::new(move || match recipient.do_send(factory()) {
+                Err(SendError::Closed(_)) => false,
+                _ => true,
+            }),
+        });
+        self.reschedule(ctx);
+        Ok(id)
+    }
+}
+
+impl Handler<Cancel> for Scheduler {
+    type Result = ();
+
+    fn handle(&mut self, msg: Cancel, ctx: &mut Self::Context) {
+        self.entries.retain(|e| e.id != msg.0);
+        self.reschedule(ctx);
+    }
+}
@@ -166,8 +166,12 @@ impl Handler<SignalType> for ProcessSignals {
     fn handle(&mut self, sig: SignalType, _: &mut Self::Context) {
         let subscribers = std::mem::replace(&mut self.subscribers, Vec::new());
         for subscr in subscribers {
-            if subscr.do_send(Signal(sig)).is_ok() {
-                self.subscribers.push(subscr);
+            match subscr.do_send(Signal(sig)) {
+                // subscriber is stopped, mailbox reports signal to `DeadLetters`
+                // service with `DeadLetterReason::Closed` reason
+                Err(SendError::Closed(_)) => (),
+                // full mailbox is handled by subscriber's overflow policy
+                Ok(()) | Err(SendError::Full(_)) => self.subscribers.push(subscr),
             }
         }
     }
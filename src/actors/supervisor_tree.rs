@@ -0,0 +1,258 @@
+//! Supervision trees
+//!
+//! `SupervisorTree` starts ordered list of child actors and restarts them
+//! according to supervision strategy when they stop. Children are described
+//! by factory closures that start an actor and return its address, so
+//! children could be of different types and could run in different arbiters.
+//! `SupervisorTree` is an actor itself, trees could be nested.
+//!
+//! If children restart too often, see `SupervisorTree::intensity()`, tree
+//! stops all children and stops itself with `StopReason::Escalated` reason,
+//! so parent tree could handle the failure.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # extern crate actix;
+//! use actix::prelude::*;
+//! use actix::actors::supervisor_tree::{SupervisorTree, Strategy};
+//!
+//! struct Db;
+//!
+//! impl Actor for Db {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! struct Session;
+//!
+//! impl Actor for Session {
+//!     type Context = Context<Self>;
+//! #   fn started(&mut self, _: &mut Self::Context) {
+//! #       Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//! #   }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let _tree: Addr<Syn, _> = SupervisorTree::new(Strategy::RestForOne)
+//!         .child("db", || Db.start())
+//!         .child("sessions", || {
+//!             SupervisorTree::new(Strategy::OneForOne)
+//!                 .child("session", || Session.start())
+//!                 .start()
+//!         })
+//!         .start();
+//!
+//!     sys.run();
+//! }
+//! ```
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use prelude::*;
+use clock;
+use msgs::Terminated;
+
+
+/// Supervision strategy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Restart only stopped child
+    OneForOne,
+    /// Stop all other children and restart all of them
+    OneForAll,
+    /// Stop children started after stopped child and restart
+    /// stopped child and all of them
+    RestForOne,
+}
+
+/// When child has to be restarted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Restart {
+    /// Always restart child
+    Permanent,
+    /// Restart child only if it stops abnormally
+    Transient,
+    /// Never restart child
+    Temporary,
+}
+
+/// Address of a running child
+trait Child {
+    fn id(&self) -> ActorId;
+
+    fn watch(&self, ctx: &mut Context<SupervisorTree>);
+
+    fn stop(&self, ctx: &mut Context<SupervisorTree>);
+}
+
+impl<A> Child for Addr<Syn, A> where A: Actor<Context=Context<A>> {
+    fn id(&self) -> ActorId {
+        Addr::id(self)
+    }
+
+    fn watch(&self, ctx: &mut Context<SupervisorTree>) {
+        ctx.watch(self);
+    }
+
+    fn stop(&self, ctx: &mut Context<SupervisorTree>) {
+        ctx.unwatch(self);
+        Addr::stop(self, StopReason::Normal);
+    }
+}
+
+struct ChildSpec {
+    name: String,
+    restart: Restart,
+    factory: Box<Fn() -> Box<Child>>,
+    child: Option<Box<Child>>,
+}
+
+/// Supervisor of heterogeneous child actors
+pub struct SupervisorTree {
+    strategy: Strategy,
+    children: Vec<ChildSpec>,
+    max_restarts: usize,
+    window: Duration,
+    restarts: VecDeque<Instant>,
+}
+
+impl SupervisorTree {
+    /// Create supervisor tree with specified strategy
+    ///
+    /// By default tree allows 3 restarts within 5 seconds.
+    pub fn new(strategy: Strategy) -> SupervisorTree {
+        SupervisorTree {
+            strategy: strategy,
+            children: Vec::new(),
+            max_restarts: 3,
+            window: Duration::from_secs(5),
+            restarts: VecDeque::new(),
+        }
+    }
+
+    /// Add permanent child, `factory` starts child actor
+    pub fn child<N, F, A>(self, name: N, factory: F) -> SupervisorTree
+        where N: Into<String>,
+              F: Fn() -> Addr<Syn, A> + 'static,
+              A: Actor<Context=Context<A>>
+    {
+        self.child_with(name, Restart::Permanent, factory)
+    }
+
+    /// Add child with specified restart type
+    pub fn child_with<N, F, A>(mut self, name: N, restart: Restart, factory: F) -> SupervisorTree
+        where N: Into<String>,
+              F: Fn() -> Addr<Syn, A> + 'static,
+              A: Actor<Context=Context<A>>
+    {
+        self.children.push(ChildSpec {
+            name: name.into(),
+            restart: restart,
+            factory: Box::new(move || -> Box<Child> { Box::new(factory()) }),
+            child: None,
+        });
+        self
+    }
+
+    /// Allow at most `max_restarts` restarts within `window`
+    pub fn intensity(mut self, max_restarts: usize, window: Duration) -> SupervisorTree {
+        self.max_restarts = max_restarts;
+        self.window = window;
+        self
+    }
+
+    fn start_child(&mut self, idx: usize, ctx: &mut Context<Self>) {
+        let child = (self.children[idx].factory)();
+        child.watch(ctx);
+        self.children[idx].child = Some(child);
+    }
+
+    fn stop_child(&mut self, idx: usize, ctx: &mut Context<Self>) {
+        if let Some(child) = self.children[idx].child.take() {
+            child.stop(ctx);
+        }
+    }
+
+    // Record restart, returns false if restart intensity is exceeded
+    fn record_restart(&mut self) -> bool {
+        let now = clock::now();
+        while let Some(&at) = self.restarts.front() {
+            if now.duration_since(at) < self.window {
+                break
+            }
+            self.restarts.pop_front();
+        }
+        if self.restarts.len() >= self.max_restarts {
+            false
+        } else {
+            self.restarts.push_back(now);
+            true
+        }
+    }
+}
+
+impl Actor for SupervisorTree {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        for idx in 0..self.children.len() {
+            self.start_child(idx, ctx);
+        }
+    }
+
+    fn stopped(&mut self, ctx: &mut Self::Context) {
+        // stop children in reverse start order
+        for idx in (0..self.children.len()).rev() {
+            self.stop_child(idx, ctx);
+        }
+    }
+}
+
+impl Handler<Terminated> for SupervisorTree {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, ctx: &mut Self::Context) {
+        let idx = match self.children.iter().position(
+            |spec| spec.child.as_ref().map(|c| c.id()) == Some(msg.id))
+        {
+            Some(idx) => idx,
+            None => return,
+        };
+        self.children[idx].child = None;
+
+        let restart = match self.children[idx].restart {
+            Restart::Permanent => true,
+            Restart::Transient => !msg.reason.is_normal(),
+            Restart::Temporary => false,
+        };
+        if !restart {
+            debug!("Child {} stopped: {:?}", self.children[idx].name, msg.reason);
+            return
+        }
+
+        if !self.record_restart() {
+            warn!("Child {} stopped: {:?}, restart intensity exceeded",
+                  self.children[idx].name, msg.reason);
+            ctx.stop_with(StopReason::Escalated);
+            return
+        }
+        info!("Restarting child {}: {:?}", self.children[idx].name, msg.reason);
+
+        let range = match self.strategy {
+            Strategy::OneForOne => idx..idx+1,
+            Strategy::OneForAll => 0..self.children.len(),
+            Strategy::RestForOne => idx..self.children.len(),
+        };
+        for i in range.clone().rev() {
+            self.stop_child(i, ctx);
+        }
+        for i in range {
+            // temporary children are never restarted
+            if self.children[i].restart != Restart::Temporary {
+                self.start_child(i, ctx);
+            }
+        }
+    }
+}
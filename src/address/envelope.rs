@@ -1,12 +1,15 @@
 use std::marker::PhantomData;
-use futures::sync::oneshot::Sender as SyncSender;
+use std::collections::VecDeque;
 use futures::unsync::oneshot::Sender as UnsyncSender;
 
 use actor::{Actor, AsyncContext};
 use context::Context;
 use handler::{Handler, Message, MessageResponse};
+use trace::{self, TraceContext};
+use utils::type_name;
 use super::{Syn, Unsync,
             MessageDestination, MessageDestinationTransport};
+use super::sync_channel::SyncResponseSender;
 
 
 /// Converter trait, packs message to suitable envelope
@@ -62,7 +65,7 @@ impl<A, M> ToEnvelope<Syn, A, M> for Context<A>
     where A: Actor<Context=Context<A>> + Handler<M>,
           M: Message + Send + 'static, M::Result: Send,
 {
-    fn pack(msg: M, tx: Option<SyncSender<M::Result>>) -> SyncEnvelope<A> {
+    fn pack(msg: M, tx: Option<SyncResponseSender<M::Result>>) -> SyncEnvelope<A> {
         SyncEnvelope::new(msg, tx)
     }
 }
@@ -75,23 +78,66 @@ impl<T, A, M> ToEnvelope<Unsync, A, M> for T
     }
 }
 
-pub struct SyncEnvelope<A: Actor>(Box<EnvelopeProxy<Actor=A> + Send>);
+/// Insert item into priority ordered buffer.
+///
+/// Items with higher priority go first, items with same priority keep FIFO order.
+pub(crate) fn push_by_priority<T, F>(buf: &mut VecDeque<T>, item: T, priority: F)
+    where F: Fn(&T) -> u32
+{
+    let prio = priority(&item);
+    let mut idx = buf.len();
+    while idx > 0 && priority(&buf[idx-1]) < prio {
+        idx -= 1;
+    }
+    buf.insert(idx, item);
+}
+
+pub struct SyncEnvelope<A: Actor> {
+    proxy: Box<EnvelopeProxy<Actor=A> + Send>,
+    priority: u32,
+    message: &'static str,
+    trace: Option<TraceContext>,
+}
 
 unsafe impl<A: Actor> Send for SyncEnvelope<A> {}
 
 impl<A: Actor> SyncEnvelope<A> {
 
-    pub fn new<M>(msg: M, tx: Option<SyncSender<M::Result>>) -> SyncEnvelope<A>
+    pub fn new<M>(msg: M, tx: Option<SyncResponseSender<M::Result>>) -> SyncEnvelope<A>
         where A: Handler<M>, A::Context: AsyncContext<A>,
               M: Message + Send + 'static, M::Result: Send
     {
-        SyncEnvelope(Box::new(SyncEnvelopeProxy{msg: Some(msg),
-                                                tx: tx,
-                                                act: PhantomData}))
+        let priority = msg.priority();
+        SyncEnvelope{proxy: Box::new(SyncEnvelopeProxy{msg: Some(msg),
+                                                       tx: tx,
+                                                       act: PhantomData}),
+                     priority: priority,
+                     message: type_name::<M>(),
+                     trace: trace::child()}
     }
 
     pub fn with_proxy(proxy: Box<EnvelopeProxy<Actor=A> + Send>) -> SyncEnvelope<A> {
-        SyncEnvelope(proxy)
+        SyncEnvelope{proxy: proxy, priority: 0, message: "unknown",
+                     trace: trace::child()}
+    }
+
+    pub(crate) fn set_message_type(&mut self, message: &'static str) {
+        self.message = message;
+    }
+
+    /// Priority of the enclosed message
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Type name of the enclosed message
+    pub fn message_type(&self) -> &'static str {
+        self.message
+    }
+
+    /// Trace context of the enclosed message
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        self.trace
     }
 }
 
@@ -99,14 +145,15 @@ impl<A: Actor> EnvelopeProxy for SyncEnvelope<A> {
     type Actor = A;
 
     fn handle(&mut self, act: &mut Self::Actor, ctx: &mut <Self::Actor as Actor>::Context) {
-        self.0.handle(act, ctx)
+        let _guard = enter(self.trace, self.message);
+        self.proxy.handle(act, ctx)
     }
 }
 
 pub struct SyncEnvelopeProxy<A, M> where M: Message + Send {
     act: PhantomData<A>,
     msg: Option<M>,
-    tx: Option<SyncSender<M::Result>>,
+    tx: Option<SyncResponseSender<M::Result>>,
 }
 
 unsafe impl<A, M: Message + Send> Send for SyncEnvelopeProxy<A, M> {}
@@ -130,7 +177,12 @@ impl<A, M> EnvelopeProxy for SyncEnvelopeProxy<A, M>
     }
 }
 
-pub struct UnsyncEnvelope<A: Actor>(Box<EnvelopeProxy<Actor=A>>);
+pub struct UnsyncEnvelope<A: Actor> {
+    proxy: Box<EnvelopeProxy<Actor=A>>,
+    priority: u32,
+    message: &'static str,
+    trace: Option<TraceContext>,
+}
 
 impl<A: Actor> UnsyncEnvelope<A> {
 
@@ -138,9 +190,28 @@ impl<A: Actor> UnsyncEnvelope<A> {
         where A: Handler<M>, A::Context: AsyncContext<A>,
               M: Message + 'static
     {
-        UnsyncEnvelope(Box::new(UnsyncEnvelopeProxy{msg: Some(msg),
-                                                    tx: tx,
-                                                    act: PhantomData}))
+        let priority = msg.priority();
+        UnsyncEnvelope{proxy: Box::new(UnsyncEnvelopeProxy{msg: Some(msg),
+                                                           tx: tx,
+                                                           act: PhantomData}),
+                       priority: priority,
+                       message: type_name::<M>(),
+                       trace: trace::child()}
+    }
+
+    /// Priority of the enclosed message
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Type name of the enclosed message
+    pub fn message_type(&self) -> &'static str {
+        self.message
+    }
+
+    /// Trace context of the enclosed message
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        self.trace
     }
 }
 
@@ -149,8 +220,17 @@ impl<A: Actor> EnvelopeProxy for UnsyncEnvelope<A> {
 
     #[inline]
     fn handle(&mut self, act: &mut Self::Actor, ctx: &mut <Self::Actor as Actor>::Context) {
-        self.0.handle(act, ctx)
+        let _guard = enter(self.trace, self.message);
+        self.proxy.handle(act, ctx)
+    }
+}
+
+/// Make message's trace context current for the time of handling
+fn enter(trace: Option<TraceContext>, message: &'static str) -> trace::Guard {
+    if let Some(ref trace) = trace {
+        trace!(target: "actix::trace", "{} message={}", trace, message);
     }
+    trace::enter(trace)
 }
 
 struct UnsyncEnvelopeProxy<A, M> where M: Message {
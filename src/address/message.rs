@@ -2,10 +2,12 @@ use std::time::Duration;
 use std::marker::PhantomData;
 
 use futures::{Async, Future, Poll};
-use tokio_core::reactor::Timeout;
 
-use arbiter::Arbiter;
+use actors::dead_letters::{dead_letter, report};
+use clock::Delay;
 use handler::{Handler, Message};
+use msgs::DeadLetterReason;
+use utils::type_name;
 
 use super::{ToEnvelope, SendError, MailboxError};
 use super::{MessageDestination, MessageDestinationTransport,
@@ -22,7 +24,7 @@ pub struct Request<T, A, M>
 {
     rx: Option<T::ResultReceiver>,
     info: Option<(T::Transport, M)>,
-    timeout: Option<Timeout>,
+    timeout: Option<Delay>,
     act: PhantomData<A>,
 }
 
@@ -39,14 +41,17 @@ impl<T, A, M> Request<T, A, M>
 
     /// Set message delivery timeout
     pub fn timeout(mut self, dur: Duration) -> Self {
-        self.timeout = Some(Timeout::new(dur, Arbiter::handle()).unwrap());
+        self.timeout = Some(Delay::new(dur));
         self
     }
 
     fn poll_timeout(&mut self) -> Poll<M::Result, MailboxError> {
         if let Some(ref mut timeout) = self.timeout {
             match timeout.poll() {
-                Ok(Async::Ready(())) => Err(MailboxError::Timeout),
+                Ok(Async::Ready(())) => {
+                    dead_letter::<A, M>(DeadLetterReason::Timeout);
+                    Err(MailboxError::Timeout)
+                }
                 Ok(Async::NotReady) => Ok(Async::NotReady),
                 Err(_) => unreachable!()
             }
@@ -83,7 +88,7 @@ impl<T, A, M> Future for Request<T, A, M>
                     self.rx = Some(rx);
                     self.poll_timeout()
                 }
-                Err(_) => Err(MailboxError::Closed),
+                Err(err) => Err(err),
             }
         } else {
             Err(MailboxError::Closed)
@@ -98,7 +103,8 @@ pub struct RecipientRequest<T, M>
 {
     rx: Option<T::ResultReceiver>,
     info: Option<(T::Transport, M)>,
-    timeout: Option<Timeout>,
+    timeout: Option<Delay>,
+    actor: Option<&'static str>,
 }
 
 impl<T, M> RecipientRequest<T, M> where T: MessageRecipient<M>, M: Message + 'static
@@ -106,19 +112,31 @@ impl<T, M> RecipientRequest<T, M> where T: MessageRecipient<M>, M: Message + 'st
     pub fn new(rx: Option<T::ResultReceiver>, info: Option<(T::Transport, M)>)
                -> RecipientRequest<T, M>
     {
-        RecipientRequest{rx: rx, info: info, timeout: None}
+        RecipientRequest{rx: rx, info: info, timeout: None, actor: None}
+    }
+
+    /// Set type name of the destination actor
+    pub(crate) fn set_actor(mut self, actor: Option<&'static str>) -> Self {
+        self.actor = actor;
+        self
     }
 
     /// Set message delivery timeout
     pub fn timeout(mut self, dur: Duration) -> Self {
-        self.timeout = Some(Timeout::new(dur, Arbiter::handle()).unwrap());
+        self.timeout = Some(Delay::new(dur));
         self
     }
 
     fn poll_timeout(&mut self) -> Poll<M::Result, MailboxError> {
         if let Some(ref mut timeout) = self.timeout {
             match timeout.poll() {
-                Ok(Async::Ready(())) => Err(MailboxError::Timeout),
+                Ok(Async::Ready(())) => {
+                    // mock recipient does not have destination actor
+                    if let Some(actor) = self.actor {
+                        report(actor, type_name::<M>(), DeadLetterReason::Timeout);
+                    }
+                    Err(MailboxError::Timeout)
+                }
                 Ok(Async::NotReady) => Ok(Async::NotReady),
                 Err(_) => unreachable!()
             }
@@ -152,7 +170,7 @@ impl<T, M> Future for RecipientRequest<T, M> where T: MessageRecipient<M>, M: Me
                     self.rx = Some(rx);
                     self.poll_timeout()
                 }
-                Err(_) => Err(MailboxError::Closed),
+                Err(err) => Err(err),
             }
         } else {
             Err(MailboxError::Closed)
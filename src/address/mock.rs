@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+use std::mem;
+use std::rc::{Rc, Weak};
+
+use futures::future::{err, ok, FutureResult};
+
+use handler::Message;
+
+use super::{MailboxError, MessageRecipient, MessageRecipientTransport,
+            MessageEnvelope, Recipient, RecipientRequest, SendError};
+
+
+/// Mock destination
+///
+/// Messages sent to `Recipient<Mock, M>` are not delivered to any actor,
+/// they get recorded by `MockRecipient<M>` which provides response.
+pub struct Mock;
+
+impl<M> MessageRecipient<M> for Mock where M: Message + 'static
+{
+    type Envelope = MessageEnvelope<M>;
+    type Transport = MockSender<M>;
+    type WeakTransport = WeakMockSender<M>;
+    type ResultReceiver = FutureResult<M::Result, MailboxError>;
+
+    fn do_send(tx: &Self::Transport, msg: M) -> Result<(), SendError<M>> {
+        tx.record(msg).map(|_| ())
+    }
+
+    fn send(tx: &Self::Transport, msg: M) -> RecipientRequest<Self, M> {
+        match tx.send(msg) {
+            Ok(rx) => RecipientRequest::new(Some(rx), None),
+            Err(_) => RecipientRequest::new(None, None),
+        }
+    }
+
+    fn try_send(tx: &Self::Transport, msg: M) -> Result<(), SendError<M>> {
+        tx.record(msg).map(|_| ())
+    }
+
+    fn clone(tx: &Self::Transport) -> Self::Transport {
+        MockSender{inner: Rc::clone(&tx.inner)}
+    }
+
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport {
+        WeakMockSender{inner: Rc::downgrade(&tx.inner)}
+    }
+
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport> {
+        tx.inner.upgrade().map(|inner| MockSender{inner: inner})
+    }
+
+    fn clone_weak(tx: &Self::WeakTransport) -> Self::WeakTransport {
+        WeakMockSender{inner: Weak::clone(&tx.inner)}
+    }
+}
+
+struct MockInner<M: Message> {
+    closed: bool,
+    messages: Vec<M>,
+    handler: Box<FnMut(&M) -> Option<M::Result>>,
+}
+
+/// Transport of `Recipient<Mock, M>`
+pub struct MockSender<M: Message> {
+    inner: Rc<RefCell<MockInner<M>>>,
+}
+
+/// Weak transport of `WeakRecipient<Mock, M>`
+pub struct WeakMockSender<M: Message> {
+    inner: Weak<RefCell<MockInner<M>>>,
+}
+
+impl<M: Message> MockSender<M> {
+    // Record message and compute response
+    fn record(&self, msg: M) -> Result<Option<M::Result>, SendError<M>> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.closed {
+            return Err(SendError::Closed(msg))
+        }
+        let res = (inner.handler)(&msg);
+        inner.messages.push(msg);
+        Ok(res)
+    }
+}
+
+impl<M> MessageRecipientTransport<Mock, M> for MockSender<M> where M: Message + 'static {
+    fn send(&self, msg: M) -> Result<FutureResult<M::Result, MailboxError>, SendError<M>> {
+        match self.record(msg)? {
+            Some(res) => Ok(ok(res)),
+            None => Ok(err(MailboxError::Closed)),
+        }
+    }
+}
+
+/// Recorder of messages sent to mock recipients
+///
+/// Mock recipient replaces actor in tests of code that sends messages
+/// through `Recipient`. All messages are recorded, response to `send()` is
+/// computed by handler function, if handler returns `None` request
+/// fails with `MailboxError::Closed`.
+///
+/// ```rust
+/// # extern crate actix;
+/// # extern crate futures;
+/// use futures::Future;
+/// use actix::prelude::*;
+/// use actix::testkit::MockRecipient;
+///
+/// struct Sum(usize, usize);
+///
+/// impl Message for Sum {
+///     type Result = usize;
+/// }
+///
+/// fn main() {
+///     let mock = MockRecipient::new(|msg: &Sum| Some(msg.0 + msg.1));
+///     let recipient = mock.recipient();
+///
+///     assert_eq!(recipient.send(Sum(1, 2)).wait().unwrap(), 3);
+///     assert_eq!(mock.len(), 1);
+/// }
+/// ```
+pub struct MockRecipient<M: Message> {
+    inner: Rc<RefCell<MockInner<M>>>,
+}
+
+impl<M> MockRecipient<M> where M: Message + 'static {
+    /// Create mock recipient with handler that computes responses
+    pub fn new<F>(f: F) -> MockRecipient<M>
+        where F: FnMut(&M) -> Option<M::Result> + 'static
+    {
+        MockRecipient{inner: Rc::new(RefCell::new(MockInner{
+            closed: false,
+            messages: Vec::new(),
+            handler: Box::new(f),
+        }))}
+    }
+
+    /// Create mock recipient which responds with `M::Result::default()`
+    pub fn with_default() -> MockRecipient<M> where M::Result: Default {
+        MockRecipient::new(|_| Some(Default::default()))
+    }
+
+    /// Recipient connected to this mock
+    pub fn recipient(&self) -> Recipient<Mock, M> {
+        Recipient::new(MockSender{inner: Rc::clone(&self.inner)})
+    }
+
+    /// Number of recorded messages
+    pub fn len(&self) -> usize {
+        self.inner.borrow().messages.len()
+    }
+
+    /// Indicates if no messages are recorded
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().messages.is_empty()
+    }
+
+    /// Take recorded messages
+    pub fn take(&self) -> Vec<M> {
+        mem::replace(&mut self.inner.borrow_mut().messages, Vec::new())
+    }
+
+    /// Close mock, subsequent messages are rejected with `SendError::Closed`
+    pub fn close(&self) {
+        self.inner.borrow_mut().closed = true;
+    }
+}
@@ -12,8 +12,11 @@ pub(crate) mod sync_channel;
 mod unsync;
 mod unsync_channel;
 
-use actor::{Actor, AsyncContext};
+mod mock;
+
+use actor::{Actor, ActorId, AsyncContext, StopReason};
 use handler::{Handler, Message};
+use mailbox::Signal;
 
 pub use self::message::{Request, RecipientRequest};
 pub use self::envelope::{EnvelopeProxy, ToEnvelope, SyncEnvelope, UnsyncEnvelope,
@@ -21,7 +24,10 @@ pub use self::envelope::{EnvelopeProxy, ToEnvelope, SyncEnvelope, UnsyncEnvelope
 
 pub use self::sync::Syn;
 pub use self::unsync::Unsync;
-pub(crate) use self::sync_channel::SyncAddressReceiver;
+pub use self::mock::{Mock, MockRecipient};
+pub(crate) use self::sync_channel::{SignalSender, SyncAddressReceiver};
+pub use self::sync_channel::{SyncResponse, SyncResponseSender};
+pub use self::unsync_channel::UnsyncResponse;
 pub(crate) use self::unsync_channel::UnsyncAddrReceiver;
 
 
@@ -37,6 +43,8 @@ pub enum MailboxError {
     Closed,
     #[fail(display="Message delivery timed out")]
     Timeout,
+    #[fail(display="Message handler panicked")]
+    Panicked,
 }
 
 impl<T> SendError<T> {
@@ -107,13 +115,44 @@ impl<A> ActorAddress<A, ()> for A where A: Actor {
     }
 }
 
-pub trait Destination<A>: Sized {
+mod sealed {
+    /// Transport traits are implemented only by actix transports
+    pub trait Sealed {}
+
+    impl Sealed for super::Syn {}
+    impl Sealed for super::Unsync {}
+    impl Sealed for super::Mock {}
+}
+
+/// Actor address transport
+///
+/// This trait is sealed, it is implemented by `Syn` and `Unsync` transports.
+pub trait Destination<A>: Sized + sealed::Sealed {
     type Transport: Clone;
+    type WeakTransport: Clone;
 
     /// Indicates if destination is still alive
     fn connected(tx: &Self::Transport) -> bool;
+
+    /// Create weak transport, weak transport does not keep actor alive
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport;
+
+    /// Upgrade weak transport, returns `None` if actor is not alive anymore
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport>;
+
+    /// Id of the destination actor
+    fn id(tx: &Self::Transport) -> ActorId;
+
+    /// Notify `watcher` when destination actor terminates
+    fn watch(tx: &Self::Transport, watcher: ActorId, signals: Box<SignalSender>);
+
+    /// Stop notifying `watcher`
+    fn unwatch(tx: &Self::Transport, watcher: ActorId);
 }
 
+/// Message sending over actor address transport
+///
+/// This trait is sealed, it is implemented by `Syn` and `Unsync` transports.
 #[allow(unused_variables)]
 pub trait MessageDestination<A, M>: Destination<A>
     where A: Handler<M>, A::Context: ToEnvelope<Self, A, M>,
@@ -122,7 +161,7 @@ pub trait MessageDestination<A, M>: Destination<A>
 {
     type Envelope;
     type ResultSender;
-    type ResultReceiver: Future<Item=M::Result>;
+    type ResultReceiver: Future<Item=M::Result, Error=MailboxError>;
 
     /// Send message unconditionally
     fn do_send(tx: &Self::Transport, msg: M);
@@ -146,12 +185,16 @@ pub trait MessageDestinationTransport<T: MessageDestination<A, M>, A, M>
     fn send(&self, msg: M) -> Result<T::ResultReceiver, SendError<M>>;
 }
 
+/// Recipient transport
+///
+/// This trait is sealed, it is implemented by `Syn`, `Unsync` and `Mock` transports.
 #[allow(unused_variables)]
-pub trait MessageRecipient<M>: Sized where M: Message + 'static
+pub trait MessageRecipient<M>: Sized + sealed::Sealed where M: Message + 'static
 {
     type Envelope: From<M>;
-    type ResultReceiver: Future<Item=M::Result>;
+    type ResultReceiver: Future<Item=M::Result, Error=MailboxError>;
     type Transport: MessageRecipientTransport<Self, M>;
+    type WeakTransport;
 
     /// Send message unconditionally
     ///
@@ -172,6 +215,15 @@ pub trait MessageRecipient<M>: Sized where M: Message + 'static
 
     /// Clone transport
     fn clone(tx: &Self::Transport) -> Self::Transport;
+
+    /// Create weak transport
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport;
+
+    /// Upgrade weak transport
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport>;
+
+    /// Clone weak transport
+    fn clone_weak(tx: &Self::WeakTransport) -> Self::WeakTransport;
 }
 
 pub trait MessageRecipientTransport<T: MessageRecipient<M>, M>
@@ -200,6 +252,19 @@ impl<T: Destination<A>, A> Addr<T, A> {
         T::connected(&self.tx)
     }
 
+    /// Id of the actor
+    pub fn id(&self) -> ActorId {
+        T::id(&self.tx)
+    }
+
+    pub(crate) fn watch(&self, watcher: ActorId, signals: Box<SignalSender>) {
+        T::watch(&self.tx, watcher, signals)
+    }
+
+    pub(crate) fn unwatch(&self, watcher: ActorId) {
+        T::unwatch(&self.tx, watcher)
+    }
+
     /// Sendm message unconditionally
     ///
     /// This method ignores actor's mailbox capacity, it silently fails if mailbox is closed.
@@ -247,6 +312,32 @@ impl<T: Destination<A>, A> Addr<T, A> {
     {
         T::recipient(self.tx)
     }
+
+    /// Get weak address of the actor
+    ///
+    /// Weak address does not keep actor alive.
+    pub fn downgrade(&self) -> WeakAddr<T, A> {
+        WeakAddr{tx: T::downgrade(&self.tx), act: PhantomData}
+    }
+}
+
+impl<A: Actor> Addr<Syn, A> {
+    pub(crate) fn link(&self, linked: ActorId, signals: Box<SignalSender>) {
+        self.tx.link(linked, signals)
+    }
+
+    pub(crate) fn unlink(&self, linked: ActorId) {
+        self.tx.unlink(linked)
+    }
+
+    pub(crate) fn signals(&self) -> Box<SignalSender> {
+        self.tx.signals()
+    }
+
+    /// Stop actor with specified reason, regardless of mailbox capacity
+    pub(crate) fn stop(&self, reason: StopReason) {
+        self.tx.signals().signal(Signal::Stop(reason))
+    }
 }
 
 impl<T: Destination<A>, A> Clone for Addr<T, A> {
@@ -255,12 +346,45 @@ impl<T: Destination<A>, A> Clone for Addr<T, A> {
     }
 }
 
+/// Weak address of the actor
+///
+/// Weak address does not count as a reference to the actor, actor stops
+/// when all strong addresses get dropped. Use `WeakAddr::upgrade()`
+/// to get `Addr` back.
+pub struct WeakAddr<T: Destination<A>, A> {
+    tx: T::WeakTransport,
+    act: PhantomData<A>,
+}
+
+unsafe impl<A: Actor> Send for WeakAddr<Syn, A> {}
+unsafe impl<A: Actor> Sync for WeakAddr<Syn, A> {}
+
+impl<T: Destination<A>, A> WeakAddr<T, A> {
+
+    /// Attempts to upgrade weak address to an `Addr`.
+    ///
+    /// Returns `None` if actor is not alive anymore.
+    pub fn upgrade(&self) -> Option<Addr<T, A>> {
+        match T::upgrade(&self.tx) {
+            Some(tx) => Some(Addr::new(tx)),
+            None => None,
+        }
+    }
+}
+
+impl<T: Destination<A>, A> Clone for WeakAddr<T, A> {
+    fn clone(&self) -> WeakAddr<T, A> {
+        WeakAddr{tx: self.tx.clone(), act: PhantomData}
+    }
+}
+
 /// `Subscriber` type allows to send one specific message to an actor.
 ///
 /// You can get subscriber with `Addr<_, _>::subscriber()` method.
 /// It is possible to use `Clone::clone()` method to get cloned subscriber.
 pub struct Recipient<T: MessageRecipient<M>, M: Message + 'static> {
     tx: T::Transport,
+    actor: Option<&'static str>,
     msg: PhantomData<M>,
 }
 
@@ -274,7 +398,13 @@ impl<T, M> Recipient<T, M>
 {
     /// Create new subscriber
     pub fn new(tx: T::Transport) -> Recipient<T, M> {
-        Recipient{tx: tx, msg: PhantomData}
+        Recipient{tx: tx, actor: None, msg: PhantomData}
+    }
+
+    /// Create new subscriber of actor `actor`, type name of the actor
+    /// is used for dead letters reports
+    pub(crate) fn with_actor(tx: T::Transport, actor: &'static str) -> Recipient<T, M> {
+        Recipient{tx: tx, actor: Some(actor), msg: PhantomData}
     }
 
     /// Send message
@@ -297,7 +427,19 @@ impl<T, M> Recipient<T, M>
     /// Communication channel to the actor is bounded. if returned `Request`
     /// object get dropped, message cancels.
     pub fn send(&self, msg: M) -> RecipientRequest<T, M> {
-        T::send(&self.tx, msg)
+        T::send(&self.tx, msg).set_actor(self.actor)
+    }
+
+    /// Type name of the destination actor, if known
+    pub(crate) fn actor(&self) -> Option<&'static str> {
+        self.actor
+    }
+
+    /// Get weak recipient
+    ///
+    /// Weak recipient does not keep actor alive.
+    pub fn downgrade(&self) -> WeakRecipient<T, M> {
+        WeakRecipient{tx: T::downgrade(&self.tx), actor: self.actor, msg: PhantomData}
     }
 }
 
@@ -305,6 +447,43 @@ impl<T, M> Clone for Recipient<T, M>
     where T: MessageRecipient<M>, M: Message + 'static
 {
     fn clone(&self) -> Recipient<T, M> {
-        Recipient{tx: T::clone(&self.tx), msg: PhantomData}
+        Recipient{tx: T::clone(&self.tx), actor: self.actor, msg: PhantomData}
+    }
+}
+
+/// Weak version of `Recipient`
+///
+/// Weak recipient does not keep actor alive, use
+/// `WeakRecipient::upgrade()` to get `Recipient` back.
+pub struct WeakRecipient<T: MessageRecipient<M>, M: Message + 'static> {
+    tx: T::WeakTransport,
+    actor: Option<&'static str>,
+    msg: PhantomData<M>,
+}
+
+unsafe impl<M> Send for WeakRecipient<Syn, M>
+    where M: Message + Send + 'static, M::Result: Send {}
+unsafe impl<M> Sync for WeakRecipient<Syn, M>
+    where M: Message + Send + 'static, M::Result: Send {}
+
+impl<T, M> WeakRecipient<T, M>
+    where T: MessageRecipient<M>, M: Message + 'static
+{
+    /// Attempts to upgrade weak recipient to a `Recipient`.
+    ///
+    /// Returns `None` if actor is not alive anymore.
+    pub fn upgrade(&self) -> Option<Recipient<T, M>> {
+        match T::upgrade(&self.tx) {
+            Some(tx) => Some(Recipient{tx: tx, actor: self.actor, msg: PhantomData}),
+            None => None,
+        }
+    }
+}
+
+impl<T, M> Clone for WeakRecipient<T, M>
+    where T: MessageRecipient<M>, M: Message + 'static
+{
+    fn clone(&self) -> WeakRecipient<T, M> {
+        WeakRecipient{tx: T::clone_weak(&self.tx), actor: self.actor, msg: PhantomData}
     }
 }
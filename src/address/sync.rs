@@ -1,11 +1,12 @@
-use futures::sync::oneshot::{Sender, Receiver};
 
-use actor::Actor;
+use actor::{Actor, ActorId};
 use handler::{Handler, Message};
+use utils::type_name;
 
 use super::envelope::{ToEnvelope, SyncEnvelope, SyncMessageEnvelope};
-use super::sync_channel::{SyncSender, SyncAddressSender};
-use super::{Request, Recipient, RecipientRequest};
+use super::sync_channel::{SyncSender, SyncAddressSender, SyncResponse, SyncResponseSender,
+                          WeakSyncSender, WeakSyncAddressSender};
+use super::{Request, Recipient, RecipientRequest, SignalSender};
 use super::{Destination, MessageDestination, MessageRecipient, SendError};
 
 
@@ -15,11 +16,32 @@ pub struct Syn;
 impl<A: Actor> Destination<A> for Syn
 {
     type Transport = SyncAddressSender<A>;
+    type WeakTransport = WeakSyncAddressSender<A>;
 
     /// Indicates if actor is still alive
     fn connected(tx: &Self::Transport) -> bool {
         tx.connected()
     }
+
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport {
+        tx.downgrade()
+    }
+
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport> {
+        tx.upgrade()
+    }
+
+    fn id(tx: &Self::Transport) -> ActorId {
+        tx.id()
+    }
+
+    fn watch(tx: &Self::Transport, watcher: ActorId, signals: Box<SignalSender>) {
+        tx.watch(watcher, signals)
+    }
+
+    fn unwatch(tx: &Self::Transport, watcher: ActorId) {
+        tx.unwatch(watcher)
+    }
 }
 
 impl<A: Actor, M> MessageDestination<A, M> for Syn
@@ -27,8 +49,8 @@ impl<A: Actor, M> MessageDestination<A, M> for Syn
           M: Message + Send + 'static, M::Result: Send,
 {
     type Envelope = SyncEnvelope<A>;
-    type ResultSender = Sender<M::Result>;
-    type ResultReceiver = Receiver<M::Result>;
+    type ResultSender = SyncResponseSender<M::Result>;
+    type ResultReceiver = SyncResponse<M::Result>;
 
     fn do_send(tx: &Self::Transport, msg: M) {
         let _ = tx.do_send(msg);
@@ -47,7 +69,7 @@ impl<A: Actor, M> MessageDestination<A, M> for Syn
     }
 
     fn recipient(tx: Self::Transport) -> Recipient<Self, M> {
-        Recipient::new(tx.into_sender())
+        Recipient::with_actor(tx.into_sender(), type_name::<A>())
     }
 }
 
@@ -55,8 +77,9 @@ impl<M> MessageRecipient<M> for Syn
     where M: Message + Send + 'static, M::Result: Send
 {
     type Transport = Box<SyncSender<M>>;
+    type WeakTransport = Box<WeakSyncSender<M>>;
     type Envelope = SyncMessageEnvelope<M>;
-    type ResultReceiver = Receiver<M::Result>;
+    type ResultReceiver = SyncResponse<M::Result>;
 
     fn do_send(tx: &Self::Transport, msg: M) -> Result<(), SendError<M>> {
         tx.do_send(msg)
@@ -79,4 +102,16 @@ impl<M> MessageRecipient<M> for Syn
     fn clone(tx: &Self::Transport) -> Self::Transport {
         tx.boxed()
     }
+
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport {
+        tx.downgrade()
+    }
+
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport> {
+        tx.upgrade()
+    }
+
+    fn clone_weak(tx: &Self::WeakTransport) -> Self::WeakTransport {
+        tx.boxed()
+    }
 }
@@ -1,22 +1,83 @@
 //! This is copy of [sync/mpsc/](https://github.com/alexcrichton/futures-rs)
 use std::{usize, thread};
 use std::cell::Cell;
+use std::collections::VecDeque;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{SeqCst, Relaxed};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
 use futures::task::{self, Task};
-use futures::{Async, Poll, Stream};
-use futures::sync::oneshot::{channel as sync_channel, Receiver};
+use futures::{Async, Future, Poll, Stream};
+use futures::sync::oneshot::{channel as sync_channel, Receiver, Sender};
 
-use actor::Actor;
+use actor::{Actor, ActorId, StopReason};
+use actors::dead_letters::{dead_letter, report};
 use handler::{Handler, Message};
+use mailbox::{MailboxPolicy, Overflow, Signal, Watchers};
+use msgs::DeadLetterReason;
+use utils::type_name;
 
-use super::{SendError, Syn, MessageDestinationTransport, MessageRecipientTransport};
+use super::{MailboxError, SendError, Syn,
+            MessageDestinationTransport, MessageRecipientTransport};
 use super::queue::{Queue, PopResult};
-use super::envelope::{ToEnvelope, SyncEnvelope};
+use super::envelope::{push_by_priority, ToEnvelope, SyncEnvelope};
 
 
+/// Response of the actor, resolves to `MailboxError::Panicked`
+/// if message handler panicked.
+pub struct SyncResponse<T> {
+    rx: Receiver<Result<T, MailboxError>>,
+}
+
+impl<T> Future for SyncResponse<T> {
+    type Item = T;
+    type Error = MailboxError;
+
+    fn poll(&mut self) -> Poll<T, MailboxError> {
+        match self.rx.poll() {
+            Ok(Async::Ready(Ok(item))) => Ok(Async::Ready(item)),
+            Ok(Async::Ready(Err(err))) => Err(err),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(_) => Err(MailboxError::Closed),
+        }
+    }
+}
+
+/// Sending side of `SyncResponse`
+///
+/// If sender is dropped during unwinding from panicked message handler,
+/// response resolves to `MailboxError::Panicked`.
+pub struct SyncResponseSender<T>(Option<Sender<Result<T, MailboxError>>>);
+
+impl<T> SyncResponseSender<T> {
+    /// Check if receiving side of the response is dropped
+    pub fn is_canceled(&self) -> bool {
+        self.0.as_ref().map(|tx| tx.is_canceled()).unwrap_or(true)
+    }
+
+    /// Complete response with `item`
+    pub fn send(mut self, item: T) {
+        if let Some(tx) = self.0.take() {
+            let _ = tx.send(Ok(item));
+        }
+    }
+}
+
+impl<T> Drop for SyncResponseSender<T> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            if let Some(tx) = self.0.take() {
+                let _ = tx.send(Err(MailboxError::Panicked));
+            }
+        }
+    }
+}
+
+pub(crate) fn response<T>() -> (SyncResponseSender<T>, SyncResponse<T>) {
+    let (tx, rx) = sync_channel();
+    (SyncResponseSender(Some(tx)), SyncResponse{rx: rx})
+}
+
 pub trait SyncSender<M>: Send
     where M::Result: Send,
           M: Message + Send + 'static
@@ -25,9 +86,29 @@ pub trait SyncSender<M>: Send
 
     fn try_send(&self, msg: M) -> Result<(), SendError<M>>;
 
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>>;
+    fn send(&self, msg: M) -> Result<SyncResponse<M::Result>, SendError<M>>;
 
     fn boxed(&self) -> Box<SyncSender<M>>;
+
+    fn downgrade(&self) -> Box<WeakSyncSender<M>>;
+}
+
+pub trait WeakSyncSender<M>: Send
+    where M::Result: Send,
+          M: Message + Send + 'static
+{
+    fn upgrade(&self) -> Option<Box<SyncSender<M>>>;
+
+    fn boxed(&self) -> Box<WeakSyncSender<M>>;
+}
+
+/// Sender of context signals, see `mailbox::Signal`
+///
+/// Signal sender does not count as a sender, so it does not keep
+/// the channel open.
+pub trait SignalSender: Send {
+    /// Deliver signal to the actor, signal is dropped if actor is gone
+    fn signal(&self, signal: Signal);
 }
 
 /// The transmission end of a channel which is used to send values.
@@ -49,6 +130,15 @@ pub struct SyncAddressSender<A: Actor> {
 
 unsafe impl<A: Actor> Sync for SyncAddressSender<A> {}
 
+/// Weak version of the transmission end of a channel.
+///
+/// Weak sender is not counted as a sender, so it does not keep the channel
+/// open. It could be upgraded to `SyncAddressSender` while at least one
+/// strong sender exists.
+pub struct WeakSyncAddressSender<A: Actor> {
+    inner: Weak<Inner<A>>,
+}
+
 trait AssertKinds: Send + Sync + Clone {}
 
 
@@ -59,6 +149,13 @@ trait AssertKinds: Send + Sync + Clone {}
 /// `channel` method.
 pub struct SyncAddressReceiver<A: Actor> {
     inner: Arc<Inner<A>>,
+
+    // Messages moved out of the message queue, ordered by priority.
+    // Only used if receiver runs in priority mode.
+    pending: VecDeque<SyncEnvelope<A>>,
+
+    // Deliver messages in priority order
+    priority: bool,
 }
 
 struct Inner<A: Actor> {
@@ -78,6 +175,18 @@ struct Inner<A: Actor> {
     // Number of senders in existence
     num_senders: AtomicUsize,
 
+    // Mailbox overflow policy
+    policy: Mutex<MailboxPolicy>,
+
+    // Number of messages that receiver has to drop, see `MailboxPolicy::DropOldest`
+    evict: AtomicUsize,
+
+    // Actors watching this actor
+    watchers: Mutex<Watchers>,
+
+    // Signals for receiver's context
+    signals: Mutex<VecDeque<Signal>>,
+
     // Handle to the receiver's task.
     recv_task: Mutex<ReceiverTask>,
 }
@@ -156,6 +265,13 @@ impl SenderTask {
 /// The `Receiver` returned implements the `Stream` trait and has access to any
 /// number of the associated combinators for transforming the result.
 pub fn channel<A: Actor>(buffer: usize) -> (SyncAddressSender<A>, SyncAddressReceiver<A>) {
+    channel_with_id(buffer, ActorId::next())
+}
+
+/// Creates channel for actor with specific id
+pub(crate) fn channel_with_id<A: Actor>(buffer: usize, id: ActorId)
+                                        -> (SyncAddressSender<A>, SyncAddressReceiver<A>)
+{
     // Check that the requested buffer size does not exceed the maximum buffer
     // size permitted by the system.
     assert!(buffer < MAX_BUFFER, "requested buffer size too large");
@@ -166,6 +282,10 @@ pub fn channel<A: Actor>(buffer: usize) -> (SyncAddressSender<A>, SyncAddressRec
         message_queue: Queue::new(),
         parked_queue: Queue::new(),
         num_senders: AtomicUsize::new(1),
+        policy: Mutex::new(MailboxPolicy::Block),
+        evict: AtomicUsize::new(0),
+        watchers: Mutex::new(Watchers::new(id)),
+        signals: Mutex::new(VecDeque::new()),
         recv_task: Mutex::new(ReceiverTask {
             unparked: false,
             task: None,
@@ -180,6 +300,8 @@ pub fn channel<A: Actor>(buffer: usize) -> (SyncAddressSender<A>, SyncAddressRec
 
     let rx = SyncAddressReceiver {
         inner: inner,
+        pending: VecDeque::new(),
+        priority: false,
     };
 
     (tx, rx)
@@ -190,7 +312,7 @@ impl<A, M> MessageDestinationTransport<Syn, A, M> for SyncAddressSender<A>
           A::Context: ToEnvelope<Syn, A, M>,
           M: Message + Send + 'static, M::Result: Send,
 {
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>> {
+    fn send(&self, msg: M) -> Result<SyncResponse<M::Result>, SendError<M>> {
         SyncAddressSender::send(self, msg)
     }
 }
@@ -212,7 +334,7 @@ impl<A: Actor> SyncAddressSender<A> {
     /// Attempts to send a message on this `Sender<A>` with blocking.
     ///
     /// This function, must be called from inside of a task.
-    pub fn send<M>(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>>
+    pub fn send<M>(&self, msg: M) -> Result<SyncResponse<M::Result>, SendError<M>>
         where A: Handler<M>, A::Context: ToEnvelope<Syn, A, M>,
               M::Result: Send,
               M: Message + Send + 'static,
@@ -231,7 +353,7 @@ impl<A: Actor> SyncAddressSender<A> {
         // receiver is dropped.
         let park_self = match self.inc_num_messages() {
             Some(park_self) => park_self,
-            None => return Err(SendError::Closed(msg)),
+            None => return Err(Self::closed(msg)),
         };
 
         // If the channel has reached capacity, then the sender task needs to
@@ -240,7 +362,7 @@ impl<A: Actor> SyncAddressSender<A> {
             self.park(true);
             Err(SendError::Full(msg))
         } else {
-            let (tx, rx) = sync_channel();
+            let (tx, rx) = response();
             let env = <A::Context as ToEnvelope<Syn, A, M>>::pack(msg, Some(tx));
             self.queue_push_and_signal(env);
             Ok(rx)
@@ -260,14 +382,11 @@ impl<A: Actor> SyncAddressSender<A> {
 
         let park_self = match self.inc_num_messages() {
             Some(park_self) => park_self,
-            None => return Err(SendError::Closed(msg)),
+            None => return Err(Self::closed(msg)),
         };
 
         if park_self {
-            if park {
-                self.park(true);
-            }
-            Err(SendError::Full(msg))
+            self.overflow(msg, false, park)
         } else {
             let env = <A::Context as ToEnvelope<Syn, A, M>>::pack(msg, None);
             self.queue_push_and_signal(env);
@@ -277,21 +396,62 @@ impl<A: Actor> SyncAddressSender<A> {
 
     /// Send a message on this `Sender<A>` without blocking.
     ///
-    /// This function does not park current task.
+    /// This function does not park current task. If channel is full
+    /// mailbox overflow policy is applied.
     pub fn do_send<M>(&self, msg: M) -> Result<(), SendError<M>>
         where A: Handler<M>, <A as Actor>::Context: ToEnvelope<Syn, A, M>,
               M::Result: Send,
               M: Message + Send + 'static,
     {
-        if self.inc_num_messages_force().is_none() {
-            Err(SendError::Closed(msg))
-        } else {
-            let env = <A::Context as ToEnvelope<Syn, A, M>>::pack(msg, None);
-            self.queue_push_and_signal(env);
-            Ok(())
+        match self.inc_num_messages() {
+            None => Err(Self::closed(msg)),
+            Some(true) => self.overflow(msg, true, false),
+            Some(false) => {
+                let env = <A::Context as ToEnvelope<Syn, A, M>>::pack(msg, None);
+                self.queue_push_and_signal(env);
+                Ok(())
+            }
         }
     }
 
+    // Apply mailbox overflow policy to a message that does not fit into the channel
+    fn overflow<M>(&self, msg: M, force: bool, park: bool) -> Result<(), SendError<M>>
+        where A: Handler<M>, <A as Actor>::Context: ToEnvelope<Syn, A, M>,
+              M::Result: Send,
+              M: Message + Send + 'static,
+    {
+        // policy could send message to other actor, so do not hold the lock
+        let policy = self.inner.policy.lock().unwrap().clone();
+
+        match policy.on_overflow::<A, M>(force) {
+            action @ Overflow::Push | action @ Overflow::Evict => {
+                if self.inc_num_messages_force().is_none() {
+                    return Err(Self::closed(msg))
+                }
+                if action == Overflow::Evict {
+                    self.inner.evict.fetch_add(1, SeqCst);
+                }
+                let env = <A::Context as ToEnvelope<Syn, A, M>>::pack(msg, None);
+                self.queue_push_and_signal(env);
+                Ok(())
+            }
+            Overflow::Drop => Ok(()),
+            Overflow::Reject => Err(SendError::Full(msg)),
+            Overflow::Wait => {
+                if park {
+                    self.park(true);
+                }
+                Err(SendError::Full(msg))
+            }
+        }
+    }
+
+    // Report message to dead letters office, channel is closed
+    fn closed<M: 'static>(msg: M) -> SendError<M> {
+        dead_letter::<A, M>(DeadLetterReason::Closed);
+        SendError::Closed(msg)
+    }
+
     // Push message to the queue and signal to the receiver
     fn queue_push_and_signal(&self, msg: SyncEnvelope<A>) {
         // Push the message onto the message queue
@@ -353,31 +513,7 @@ impl<A: Actor> SyncAddressSender<A> {
 
     // Signal to the receiver task that a message has been enqueued
     fn signal(&self) {
-        // TODO
-        // This logic can probably be improved by guarding the lock with an
-        // atomic.
-        //
-        // Do this step first so that the lock is dropped when
-        // `unpark` is called
-        let task = {
-            let mut recv_task = self.inner.recv_task.lock().unwrap();
-
-            // If the receiver has already been unparked, then there is nothing
-            // more to do
-            if recv_task.unparked {
-                return;
-            }
-
-            // Setting this flag enables the receiving end to detect that
-            // an unpark event happened in order to avoid unnecessarily
-            // parking.
-            recv_task.unparked = true;
-            recv_task.task.take()
-        };
-
-        if let Some(task) = task {
-            task.notify();
-        }
+        self.inner.wake_receiver()
     }
 
     fn park(&self, can_park: bool) {
@@ -433,6 +569,41 @@ impl<A: Actor> SyncAddressSender<A> {
         }
     }
 
+    /// Id of the receiver actor
+    pub fn id(&self) -> ActorId {
+        self.inner.watchers.lock().unwrap().id()
+    }
+
+    /// Register watcher of the receiver actor
+    pub(crate) fn watch(&self, watcher: ActorId, signals: Box<SignalSender>) {
+        self.inner.watchers.lock().unwrap().watch(watcher, signals)
+    }
+
+    /// Remove watcher of the receiver actor
+    pub(crate) fn unwatch(&self, watcher: ActorId) {
+        self.inner.watchers.lock().unwrap().unwatch(watcher)
+    }
+
+    /// Link actor to the receiver actor
+    pub(crate) fn link(&self, linked: ActorId, signals: Box<SignalSender>) {
+        self.inner.watchers.lock().unwrap().link(linked, signals)
+    }
+
+    /// Remove link to the receiver actor
+    pub(crate) fn unlink(&self, linked: ActorId) {
+        self.inner.watchers.lock().unwrap().unlink(linked)
+    }
+
+    /// Signal sender of the receiver's context
+    pub(crate) fn signals(&self) -> Box<SignalSender> {
+        Box::new(Arc::downgrade(&self.inner))
+    }
+
+    /// Create weak version of this sender
+    pub fn downgrade(&self) -> WeakSyncAddressSender<A> {
+        WeakSyncAddressSender { inner: Arc::downgrade(&self.inner) }
+    }
+
     /// Get `Sender` for a specific message type
     pub(crate) fn into_sender<M>(self) -> Box<SyncSender<M>>
         where A: Handler<M>, A::Context: ToEnvelope<Syn, A, M>,
@@ -454,18 +625,21 @@ impl<A, M> SyncSender<M> for SyncAddressSender<A>
     fn try_send(&self, msg: M) -> Result<(), SendError<M>> {
         self.try_send(msg, true)
     }
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>> {
+    fn send(&self, msg: M) -> Result<SyncResponse<M::Result>, SendError<M>> {
         self.send(msg)
     }
     fn boxed(&self) -> Box<SyncSender<M>> {
         Box::new(self.clone())
     }
+    fn downgrade(&self) -> Box<WeakSyncSender<M>> {
+        Box::new(SyncAddressSender::downgrade(self))
+    }
 }
 
 impl<M> MessageRecipientTransport<Syn, M> for Box<SyncSender<M>>
     where M: Message + Send + 'static, M::Result: Send,
 {
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>> {
+    fn send(&self, msg: M) -> Result<SyncResponse<M::Result>, SendError<M>> {
         self.as_ref().send(msg)
     }
 }
@@ -514,6 +688,70 @@ impl<A: Actor> Drop for SyncAddressSender<A> {
     }
 }
 
+//
+//
+// ===== impl WeakSender =====
+//
+//
+impl<A: Actor> WeakSyncAddressSender<A> {
+
+    /// Attempts to upgrade weak sender to a `SyncAddressSender`.
+    ///
+    /// Returns `None` if receiver is gone or all strong senders have been dropped.
+    pub fn upgrade(&self) -> Option<SyncAddressSender<A>> {
+        let inner = match self.inner.upgrade() {
+            Some(inner) => inner,
+            None => return None,
+        };
+        if !decode_state(inner.state.load(SeqCst)).is_open {
+            return None
+        }
+
+        let mut curr = inner.num_senders.load(SeqCst);
+        loop {
+            // receiver already observed disconnect, channel can not be revived
+            if curr == 0 {
+                return None
+            }
+            if curr == inner.max_senders() {
+                panic!("cannot clone `Sender` -- too many outstanding senders");
+            }
+
+            let actual = inner.num_senders.compare_and_swap(curr, curr + 1, SeqCst);
+            if actual == curr {
+                return Some(SyncAddressSender {
+                    inner: inner,
+                    sender_task: Arc::new(Mutex::new(SenderTask::new())),
+                    maybe_parked: Cell::new(false),
+                })
+            }
+            curr = actual;
+        }
+    }
+}
+
+impl<A: Actor> Clone for WeakSyncAddressSender<A> {
+    fn clone(&self) -> WeakSyncAddressSender<A> {
+        WeakSyncAddressSender { inner: Weak::clone(&self.inner) }
+    }
+}
+
+impl<A, M> WeakSyncSender<M> for WeakSyncAddressSender<A>
+    where A: Handler<M>, A::Context: ToEnvelope<Syn, A, M>,
+          M::Result: Send,
+          M: Message + Send + 'static,
+{
+    fn upgrade(&self) -> Option<Box<SyncSender<M>>> {
+        match WeakSyncAddressSender::upgrade(self) {
+            Some(tx) => Some(Box::new(tx)),
+            None => None,
+        }
+    }
+    fn boxed(&self) -> Box<WeakSyncSender<M>> {
+        Box::new(self.clone())
+    }
+}
+
 //
 //
 // ===== impl Receiver =====
@@ -552,6 +790,61 @@ impl<A: Actor> SyncAddressReceiver<A> {
         }
     }
 
+    /// Enable or disable priority mode
+    ///
+    /// In priority mode messages with higher priority get delivered first,
+    /// messages with equal priority are delivered in FIFO order.
+    pub fn set_priority(&mut self, priority: bool) {
+        self.priority = priority;
+    }
+
+    /// Set mailbox overflow policy
+    pub fn set_policy(&mut self, policy: MailboxPolicy) {
+        *self.inner.policy.lock().unwrap() = policy;
+    }
+
+    /// Id of the actor
+    pub fn id(&self) -> ActorId {
+        self.inner.watchers.lock().unwrap().id()
+    }
+
+    /// Link actor to the receiver actor
+    pub fn link(&self, linked: ActorId, signals: Box<SignalSender>) {
+        self.inner.watchers.lock().unwrap().link(linked, signals)
+    }
+
+    /// Remove linked actor
+    pub fn unlink(&self, linked: ActorId) {
+        self.inner.watchers.lock().unwrap().unlink(linked)
+    }
+
+    /// Notify watchers about actor termination
+    pub fn terminate(&self, reason: &StopReason) {
+        self.inner.watchers.lock().unwrap().terminate(reason)
+    }
+
+    /// Signal sender of the receiver's context
+    pub fn signals(&self) -> Box<SignalSender> {
+        Box::new(Arc::downgrade(&self.inner))
+    }
+
+    /// Next signal for receiver's context, if any
+    pub fn next_signal(&self) -> Option<Signal> {
+        self.inner.signals.lock().unwrap().pop_front()
+    }
+
+    /// Number of queued messages
+    #[cfg_attr(feature="cargo-clippy", allow(len_without_is_empty))]
+    pub fn len(&self) -> usize {
+        decode_state(self.inner.state.load(SeqCst)).num_messages
+    }
+
+    /// Priority of the next message, if any.
+    pub fn peek_priority(&mut self) -> Option<u32> {
+        self.fill_pending();
+        self.pending.front().map(|env| env.priority())
+    }
+
     /// Get sender side of the channel
     pub fn sender(&mut self) -> SyncAddressSender<A> {
         // this code same as Sender::clone
@@ -581,6 +874,56 @@ impl<A: Actor> SyncAddressReceiver<A> {
     }
 
     fn next_message(&mut self) -> Async<Option<SyncEnvelope<A>>> {
+        if self.priority {
+            self.fill_pending();
+        }
+        if let Some(msg) = self.pending.pop_front() {
+            return Async::Ready(Some(msg));
+        }
+        self.pop_message()
+    }
+
+    // Drop messages evicted by senders, see `MailboxPolicy::DropOldest`
+    fn evict(&mut self) {
+        while self.inner.evict.load(SeqCst) > 0 {
+            let msg = if self.priority {
+                self.fill_pending();
+                self.pending.pop_back()
+            } else {
+                match self.next_message() {
+                    Async::Ready(msg) => msg,
+                    Async::NotReady => None,
+                }
+            };
+            match msg {
+                Some(msg) => report(
+                    type_name::<A>(), msg.message_type(), DeadLetterReason::Dropped),
+                None => return,
+            }
+            self.inner.evict.fetch_sub(1, SeqCst);
+            self.unpark_one();
+            self.dec_num_messages();
+        }
+    }
+
+    // Move queued messages to the priority ordered pending buffer. Buffer
+    // holds up to mailbox capacity messages, moved messages still count
+    // as queued until they get delivered, so senders keep being blocked.
+    fn fill_pending(&mut self) {
+        let cap = match self.inner.buffer.load(Relaxed) {
+            0 => usize::MAX,
+            cap => cap,
+        };
+        while self.pending.len() < cap {
+            match self.pop_message() {
+                Async::Ready(Some(msg)) =>
+                    push_by_priority(&mut self.pending, msg, |env| env.priority()),
+                _ => return,
+            }
+        }
+    }
+
+    fn pop_message(&mut self) -> Async<Option<SyncEnvelope<A>>> {
         // Pop off a message
         loop {
             match unsafe { self.inner.message_queue.pop() } {
@@ -666,6 +1009,8 @@ impl<A: Actor> Stream for SyncAddressReceiver<A> {
     type Error = ();
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.evict();
+
         loop {
             // Try to read a message off of the message queue.
             let msg = match self.next_message() {
@@ -733,9 +1078,12 @@ impl<A: Actor> Drop for SyncAddressReceiver<A> {
             }
         }
 
+        // Notify watchers, if actor did not notify them already
+        self.terminate(&StopReason::Normal);
+
         // Drain the channel of all pending messages
-        while self.next_message().is_ready() {
-            // ...
+        while let Async::Ready(Some(msg)) = self.next_message() {
+            report(type_name::<A>(), msg.message_type(), DeadLetterReason::Closed);
         }
     }
 }
@@ -751,6 +1099,45 @@ impl<A: Actor> Inner<A> {
     fn max_senders(&self) -> usize {
         MAX_CAPACITY - self.buffer.load(Relaxed)
     }
+
+    // Wake up the receiver task, i.e. a message or a signal has been enqueued
+    fn wake_receiver(&self) {
+        // TODO
+        // This logic can probably be improved by guarding the lock with an
+        // atomic.
+        //
+        // Do this step first so that the lock is dropped when
+        // `unpark` is called
+        let task = {
+            let mut recv_task = self.recv_task.lock().unwrap();
+
+            // If the receiver has already been unparked, then there is nothing
+            // more to do
+            if recv_task.unparked {
+                return;
+            }
+
+            // Setting this flag enables the receiving end to detect that
+            // an unpark event happened in order to avoid unnecessarily
+            // parking.
+            recv_task.unparked = true;
+            recv_task.task.take()
+        };
+
+        if let Some(task) = task {
+            task.notify();
+        }
+    }
+}
+
+impl<A: Actor> SignalSender for Weak<Inner<A>> {
+    fn signal(&self, signal: Signal) {
+        if let Some(inner) = self.upgrade() {
+            if decode_state(inner.state.load(SeqCst)).is_open {
+                inner.signals.lock().unwrap().push_back(signal);
+                inner.wake_receiver();
+            }
+        }
 }
 
 unsafe impl<A: Actor> Send for Inner<A> {}
@@ -855,4 +1242,27 @@ mod tests {
 
         sys.run();
     }
+
+    #[test]
+    fn test_priority_pending() {
+        let (tx, mut recv) = channel::<Act>(2);
+        recv.set_priority(true);
+        for _ in 0..4 {
+            let _ = tx.do_send(Ping);
+        }
+
+        // pending buffer is bounded by capacity
+        assert!(recv.peek_priority().is_some());
+        assert_eq!(recv.pending.len(), 2);
+        assert_eq!(recv.len(), 4);
+
+        // delivered message frees a slot, next one is moved to pending buffer
+        match recv.poll() {
+            Ok(Async::Ready(Some(_))) => (),
+            _ => panic!("message expected"),
+        }
+        assert_eq!(recv.len(), 3);
+        assert!(recv.peek_priority().is_some());
+        assert_eq!(recv.pending.len(), 2);
+    }
 }
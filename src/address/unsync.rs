@@ -1,12 +1,14 @@
-use futures::unsync::oneshot::{Receiver, Sender};
+use futures::unsync::oneshot::Sender;
 
-use actor::{Actor, AsyncContext};
+use actor::{Actor, ActorId, AsyncContext};
 use handler::{Handler, Message};
+use utils::type_name;
 
-use super::{Request, Recipient, RecipientRequest};
+use super::{Request, Recipient, RecipientRequest, SignalSender};
 use super::{ToEnvelope, UnsyncEnvelope, MessageEnvelope};
 use super::{Destination, MessageDestination, MessageRecipient, SendError};
-use super::unsync_channel::{UnsyncSender, UnsyncAddrSender};
+use super::unsync_channel::{UnsyncSender, UnsyncAddrSender, UnsyncResponse,
+                            WeakUnsyncSender, WeakUnsyncAddrSender};
 
 
 /// Unsync destination of the actor
@@ -18,11 +20,32 @@ impl<A: Actor> Destination<A> for Unsync
     where A::Context: AsyncContext<A>
 {
     type Transport = UnsyncAddrSender<A>;
+    type WeakTransport = WeakUnsyncAddrSender<A>;
 
     /// Indicates if actor is still alive
     fn connected(tx: &Self::Transport) -> bool {
         tx.connected()
     }
+
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport {
+        tx.downgrade()
+    }
+
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport> {
+        tx.upgrade()
+    }
+
+    fn id(tx: &Self::Transport) -> ActorId {
+        tx.id()
+    }
+
+    fn watch(tx: &Self::Transport, watcher: ActorId, signals: Box<SignalSender>) {
+        tx.watch(watcher, signals)
+    }
+
+    fn unwatch(tx: &Self::Transport, watcher: ActorId) {
+        tx.unwatch(watcher)
+    }
 }
 
 impl<A, M> MessageDestination<A, M> for Unsync
@@ -31,7 +54,7 @@ impl<A, M> MessageDestination<A, M> for Unsync
 {
     type Envelope = UnsyncEnvelope<A>;
     type ResultSender = Sender<M::Result>;
-    type ResultReceiver = Receiver<M::Result>;
+    type ResultReceiver = UnsyncResponse<M::Result>;
 
     fn do_send(tx: &Self::Transport, msg: M) {
         let _ = tx.do_send(msg);
@@ -50,7 +73,7 @@ impl<A, M> MessageDestination<A, M> for Unsync
     }
 
     fn recipient(tx: Self::Transport) -> Recipient<Self, M> {
-        Recipient::new(tx.into_sender())
+        Recipient::with_actor(tx.into_sender(), type_name::<A>())
     }
 }
 
@@ -58,7 +81,8 @@ impl<M> MessageRecipient<M> for Unsync where M: Message + 'static
 {
     type Envelope = MessageEnvelope<M>;
     type Transport = Box<UnsyncSender<M>>;
-    type ResultReceiver = Receiver<M::Result>;
+    type WeakTransport = Box<WeakUnsyncSender<M>>;
+    type ResultReceiver = UnsyncResponse<M::Result>;
 
     fn do_send(tx: &Self::Transport, msg: M) -> Result<(), SendError<M>> {
         tx.do_send(msg)
@@ -81,4 +105,16 @@ impl<M> MessageRecipient<M> for Unsync where M: Message + 'static
     fn clone(tx: &Self::Transport) -> Self::Transport {
         tx.boxed()
     }
+
+    fn downgrade(tx: &Self::Transport) -> Self::WeakTransport {
+        tx.downgrade()
+    }
+
+    fn upgrade(tx: &Self::WeakTransport) -> Option<Self::Transport> {
+        tx.upgrade()
+    }
+
+    fn clone_weak(tx: &Self::WeakTransport) -> Self::WeakTransport {
+        tx.boxed()
+    }
 }
@@ -10,32 +10,97 @@ use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use std::collections::VecDeque;
 
-use futures::{Async, Poll, Stream};
+use futures::{Async, Future, Poll, Stream};
 use futures::task::{self, Task};
 use futures::unsync::oneshot::{channel, Receiver};
 
-use actor::{Actor, AsyncContext};
+use actor::{Actor, ActorId, AsyncContext, StopReason};
+use actors::dead_letters::{dead_letter, report};
 use handler::{Handler, Message};
-use super::{SendError, Unsync, ToEnvelope,
+use mailbox::{MailboxPolicy, Overflow, Watchers};
+use msgs::DeadLetterReason;
+use utils::type_name;
+use super::{MailboxError, SendError, SignalSender, Unsync, ToEnvelope,
             MessageDestinationTransport, MessageRecipientTransport};
-use super::envelope::UnsyncEnvelope;
+use super::envelope::{push_by_priority, UnsyncEnvelope};
 
 
+/// Response of the actor
+pub struct UnsyncResponse<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> Future for UnsyncResponse<T> {
+    type Item = T;
+    type Error = MailboxError;
+
+    fn poll(&mut self) -> Poll<T, MailboxError> {
+        match self.rx.poll() {
+            Ok(Async::Ready(item)) => Ok(Async::Ready(item)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(_) => Err(MailboxError::Closed),
+        }
+    }
+}
+
 pub trait UnsyncSender<M: Message + 'static> {
     fn do_send(&self, msg: M) -> Result<(), SendError<M>>;
 
     fn try_send(&self, msg: M) -> Result<(), SendError<M>>;
 
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>>;
+    fn send(&self, msg: M) -> Result<UnsyncResponse<M::Result>, SendError<M>>;
 
     fn boxed(&self) -> Box<UnsyncSender<M>>;
+
+    fn downgrade(&self) -> Box<WeakUnsyncSender<M>>;
+}
+
+pub trait WeakUnsyncSender<M: Message + 'static> {
+    fn upgrade(&self) -> Option<Box<UnsyncSender<M>>>;
+
+    fn boxed(&self) -> Box<WeakUnsyncSender<M>>;
 }
 
 struct Shared<A: Actor> {
     buffer: VecDeque<UnsyncEnvelope<A>>,
     capacity: usize,
+    // number of strong senders, weak senders are not counted
+    senders: usize,
+    // deliver messages in priority order
+    priority: bool,
+    // overflow policy
+    policy: MailboxPolicy,
     blocked_senders: VecDeque<Task>,
     blocked_recv: Option<Task>,
+    // actors watching this actor, shared with senders
+    watchers: Rc<RefCell<Watchers>>,
+}
+
+impl<A: Actor> Shared<A> {
+    fn push(&mut self, msg: UnsyncEnvelope<A>) {
+        if self.priority {
+            push_by_priority(&mut self.buffer, msg, |env| env.priority());
+        } else {
+            self.buffer.push_back(msg);
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.capacity != 0 && self.buffer.len() >= self.capacity
+    }
+
+    // Remove oldest message, or message with lowest priority in priority mode
+    fn evict(&mut self) -> Option<UnsyncEnvelope<A>> {
+        let msg = if self.priority {
+            self.buffer.pop_back()
+        } else {
+            self.buffer.pop_front()
+        };
+        if let Some(ref msg) = msg {
+            report(type_name::<A>(), msg.message_type(), DeadLetterReason::Dropped);
+        }
+        msg
+    }
 }
 
 /// The transmission end of a channel.
@@ -43,6 +108,14 @@ struct Shared<A: Actor> {
 /// This is created by the `channel` function.
 pub struct UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
     shared: Weak<RefCell<Shared<A>>>,
+    watchers: Rc<RefCell<Watchers>>,
+}
+
+/// Weak version of the transmission end of a channel.
+///
+/// Weak sender does not keep the channel open.
+pub struct WeakUnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
+    shared: Weak<RefCell<Shared<A>>>,
 }
 
 impl<A, M> MessageDestinationTransport<Unsync, A, M> for UnsyncAddrSender<A>
@@ -50,7 +123,7 @@ impl<A, M> MessageDestinationTransport<Unsync, A, M> for UnsyncAddrSender<A>
           A::Context: AsyncContext<A> + ToEnvelope<Unsync, A, M>,
           M: Message + 'static,
 {
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>> {
+    fn send(&self, msg: M) -> Result<UnsyncResponse<M::Result>, SendError<M>> {
         UnsyncAddrSender::send(self, msg)
     }
 }
@@ -63,8 +136,28 @@ impl<A> UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
         }
     }
 
-    /// Try to put message to a receiver queue, if queue is full
-    /// return message back.
+    /// Id of the receiver actor
+    pub fn id(&self) -> ActorId {
+        self.watchers.borrow().id()
+    }
+
+    /// Register watcher of the receiver actor
+    pub(crate) fn watch(&self, watcher: ActorId, signals: Box<SignalSender>) {
+        self.watchers.borrow_mut().watch(watcher, signals)
+    }
+
+    /// Remove watcher of the receiver actor
+    pub(crate) fn unwatch(&self, watcher: ActorId) {
+        self.watchers.borrow_mut().unwatch(watcher)
+    }
+
+    /// Create weak version of this sender
+    pub fn downgrade(&self) -> WeakUnsyncAddrSender<A> {
+        WeakUnsyncAddrSender { shared: Weak::clone(&self.shared) }
+    }
+
+    /// Put message to a receiver queue, if queue is full mailbox
+    /// overflow policy is applied.
     ///
     /// This method does not register current task in receivers queue.
     pub fn do_send<M>(&self, msg: M) -> Result<(), SendError<M>>
@@ -72,12 +165,21 @@ impl<A> UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
     {
         let shared = match self.shared.upgrade() {
             Some(shared) => shared,
-            None => return Err(SendError::Closed(msg)),
+            None => return Err(closed::<A, M>(msg)),
         };
+        // evicted message has to be dropped after shared state get released
+        let mut _evicted = None;
         let mut shared = shared.borrow_mut();
 
-        shared.buffer.push_back(
-            <A::Context as ToEnvelope<Unsync, A, M>>::pack(msg, None));
+        if shared.is_full() {
+            match shared.policy.on_overflow::<A, M>(true) {
+                Overflow::Push | Overflow::Wait => (),
+                Overflow::Evict => _evicted = shared.evict(),
+                Overflow::Drop => return Ok(()),
+                Overflow::Reject => return Err(SendError::Full(msg)),
+            }
+        }
+        shared.push(<A::Context as ToEnvelope<Unsync, A, M>>::pack(msg, None));
         if let Some(task) = shared.blocked_recv.take() {
             drop(shared);
             task.notify();
@@ -86,7 +188,7 @@ impl<A> UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
     }
 
     /// Try to put message to a receiver queue, if queue is full
-    /// return message back.
+    /// mailbox overflow policy is applied.
     ///
     /// This method may register current task in receivers queue depends on
     /// state of `park` parameter.
@@ -95,48 +197,56 @@ impl<A> UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
     {
         let shared = match self.shared.upgrade() {
             Some(shared) => shared,
-            None => return Err(SendError::Closed(msg)),
+            None => return Err(closed::<A, M>(msg)),
         };
+        // evicted message has to be dropped after shared state get released
+        let mut _evicted = None;
         let mut shared = shared.borrow_mut();
 
-        if shared.capacity == 0 || shared.buffer.len() < shared.capacity {
-            shared.buffer.push_back(
-            <A::Context as ToEnvelope<Unsync, A, M>>::pack(msg, None));
-            if let Some(task) = shared.blocked_recv.take() {
-                drop(shared);
-                task.notify();
-            }
-            Ok(())
-        } else {
-            if park {
-                shared.blocked_senders.push_back(task::current());
+        if shared.is_full() {
+            match shared.policy.on_overflow::<A, M>(false) {
+                Overflow::Push => (),
+                Overflow::Evict => _evicted = shared.evict(),
+                Overflow::Drop => return Ok(()),
+                Overflow::Reject => return Err(SendError::Full(msg)),
+                Overflow::Wait => {
+                    if park {
+                        shared.blocked_senders.push_back(task::current());
+                    }
+                    return Err(SendError::Full(msg))
+                }
             }
-            Err(SendError::Full(msg))
         }
+
+        shared.push(<A::Context as ToEnvelope<Unsync, A, M>>::pack(msg, None));
+        if let Some(task) = shared.blocked_recv.take() {
+            drop(shared);
+            task.notify();
+        }
+        Ok(())
     }
 
     /// Try to put message to a receiver queue, if queue is full
     /// return message back.
     ///
     /// This method registers current task in receivers queue.
-    pub fn send<M>(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>>
+    pub fn send<M>(&self, msg: M) -> Result<UnsyncResponse<M::Result>, SendError<M>>
         where A: Handler<M>, M: Message + 'static
     {
         let shared = match self.shared.upgrade() {
             Some(shared) => shared,
-            None => return Err(SendError::Closed(msg)),
+            None => return Err(closed::<A, M>(msg)),
         };
         let mut shared = shared.borrow_mut();
 
         if shared.capacity == 0 || shared.buffer.len() < shared.capacity {
             let (tx, rx) = channel();
-            shared.buffer.push_back(
-                <A::Context as ToEnvelope<Unsync, A, M>>::pack(msg, Some(tx)));
+            shared.push(<A::Context as ToEnvelope<Unsync, A, M>>::pack(msg, Some(tx)));
             if let Some(task) = shared.blocked_recv.take() {
                 drop(shared);
                 task.notify();
             }
-            Ok(rx)
+            Ok(UnsyncResponse{rx: rx})
         } else {
             shared.blocked_senders.push_back(task::current());
             Err(SendError::Full(msg))
@@ -150,6 +260,12 @@ impl<A> UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
     }
 }
 
+// Report message to dead letters office, channel is closed
+fn closed<A: Actor, M: 'static>(msg: M) -> SendError<M> {
+    dead_letter::<A, M>(DeadLetterReason::Closed);
+    SendError::Closed(msg)
+}
+
 impl<A, M> UnsyncSender<M> for UnsyncAddrSender<A>
     where A: Actor + Handler<M>,
           A::Context: AsyncContext<A>,
@@ -161,25 +277,32 @@ impl<A, M> UnsyncSender<M> for UnsyncAddrSender<A>
     fn try_send(&self, msg: M) -> Result<(), SendError<M>> {
         self.try_send(msg, true)
     }
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>> {
+    fn send(&self, msg: M) -> Result<UnsyncResponse<M::Result>, SendError<M>> {
         self.send(msg)
     }
     fn boxed(&self) -> Box<UnsyncSender<M>> {
         Box::new(self.clone())
     }
+    fn downgrade(&self) -> Box<WeakUnsyncSender<M>> {
+        Box::new(UnsyncAddrSender::downgrade(self))
+    }
 }
 
 impl<M> MessageRecipientTransport<Unsync, M> for Box<UnsyncSender<M>>
     where M: Message + 'static,
 {
-    fn send(&self, msg: M) -> Result<Receiver<M::Result>, SendError<M>> {
+    fn send(&self, msg: M) -> Result<UnsyncResponse<M::Result>, SendError<M>> {
         self.as_ref().send(msg)
     }
 }
 
 impl<A> Clone for UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
     fn clone(&self) -> Self {
-        UnsyncAddrSender { shared: Weak::clone(&self.shared) }
+        if let Some(shared) = self.shared.upgrade() {
+            shared.borrow_mut().senders += 1;
+        }
+        UnsyncAddrSender { shared: Weak::clone(&self.shared),
+                           watchers: Rc::clone(&self.watchers) }
     }
 }
 
@@ -189,16 +312,65 @@ impl<A> Drop for UnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A>
             Some(shared) => shared,
             None => return,
         };
-        if Rc::weak_count(&shared) == 1 {
-            let task = { shared.borrow_mut().blocked_recv.take() };
-            if let Some(task) = task {
-                // Wake up receiver as its stream has ended
-                task.notify();
+        let task = {
+            let mut shared = shared.borrow_mut();
+            shared.senders -= 1;
+            if shared.senders == 0 {
+                shared.blocked_recv.take()
+            } else {
+                None
             }
+        };
+        if let Some(task) = task {
+            // Wake up receiver as its stream has ended
+            task.notify();
+        }
+    }
+}
+
+impl<A> WeakUnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
+
+    /// Attempts to upgrade weak sender to an `UnsyncAddrSender`.
+    ///
+    /// Returns `None` if receiver is gone or all strong senders have been dropped.
+    pub fn upgrade(&self) -> Option<UnsyncAddrSender<A>> {
+        let shared = match self.shared.upgrade() {
+            Some(shared) => shared,
+            None => return None,
+        };
+        let mut shared = shared.borrow_mut();
+        if shared.senders == 0 {
+            None
+        } else {
+            shared.senders += 1;
+            Some(UnsyncAddrSender { shared: Weak::clone(&self.shared),
+                                    watchers: Rc::clone(&shared.watchers) })
         }
     }
 }
 
+impl<A> Clone for WeakUnsyncAddrSender<A> where A: Actor, A::Context: AsyncContext<A> {
+    fn clone(&self) -> Self {
+        WeakUnsyncAddrSender { shared: Weak::clone(&self.shared) }
+    }
+}
+
+impl<A, M> WeakUnsyncSender<M> for WeakUnsyncAddrSender<A>
+    where A: Actor + Handler<M>,
+          A::Context: AsyncContext<A>,
+          M: Message + 'static
+{
+    fn upgrade(&self) -> Option<Box<UnsyncSender<M>>> {
+        match WeakUnsyncAddrSender::upgrade(self) {
+            Some(tx) => Some(Box::new(tx)),
+            None => None,
+        }
+    }
+    fn boxed(&self) -> Box<WeakUnsyncSender<M>> {
+        Box::new(self.clone())
+    }
+}
+
 /// The receiving end of a channel which implements the `Stream` trait.
 ///
 /// This is created by the `channel` function.
@@ -214,24 +386,47 @@ impl<A> UnsyncAddrReceiver<A> where A: Actor, A::Context: AsyncContext<A> {
     /// traits which can be used to communicate a stream of values between tasks
     /// with backpressure. The channel capacity is exactly `cap`. On average,
     /// sending a message through this channel performs no dynamic allocation.
-    pub fn new(cap: usize) -> UnsyncAddrReceiver<A> {
+    pub fn new(cap: usize, id: ActorId) -> UnsyncAddrReceiver<A> {
         UnsyncAddrReceiver {
             state: Rc::new(RefCell::new(Shared {
                 buffer: VecDeque::new(),
                 capacity: cap,
+                senders: 0,
+                priority: false,
+                policy: MailboxPolicy::Block,
                 blocked_senders: VecDeque::new(),
-                blocked_recv: None }))
+                blocked_recv: None,
+                watchers: Rc::new(RefCell::new(Watchers::new(id))) }))
         }
     }
 
+    /// Id of the actor
+    pub fn id(&self) -> ActorId {
+        self.state.borrow().watchers.borrow().id()
+    }
+
+    /// Remove linked actor
+    pub fn unlink(&self, linked: ActorId) {
+        self.state.borrow().watchers.borrow_mut().unlink(linked);
+    }
+
+    /// Notify watchers about actor termination
+    pub fn terminate(&self, reason: &StopReason) {
+        let watchers = Rc::clone(&self.state.borrow().watchers);
+        watchers.borrow_mut().terminate(reason);
+    }
+
     /// Check if receiver connected to senders
     pub fn connected(&self) -> bool {
-        Rc::weak_count(&self.state) != 0
+        self.state.borrow().senders != 0
     }
 
     /// Get the sender half
     pub fn sender(&mut self) -> UnsyncAddrSender<A> {
-        UnsyncAddrSender{shared: Rc::downgrade(&self.state)}
+        let mut shared = self.state.borrow_mut();
+        shared.senders += 1;
+        UnsyncAddrSender{shared: Rc::downgrade(&self.state),
+                         watchers: Rc::clone(&shared.watchers)}
     }
 
     /// Get channel capacity
@@ -239,6 +434,38 @@ impl<A> UnsyncAddrReceiver<A> where A: Actor, A::Context: AsyncContext<A> {
         self.state.borrow().capacity
     }
 
+    /// Enable or disable priority mode
+    ///
+    /// In priority mode messages with higher priority get delivered first,
+    /// messages with equal priority are delivered in FIFO order.
+    pub fn set_priority(&mut self, priority: bool) {
+        let mut shared = self.state.borrow_mut();
+        if priority && !shared.priority {
+            // re-order already queued messages
+            let msgs: Vec<_> = shared.buffer.drain(..).collect();
+            shared.priority = true;
+            for msg in msgs {
+                shared.push(msg);
+            }
+        }
+        shared.priority = priority;
+    }
+
+    /// Set mailbox overflow policy
+    pub fn set_policy(&mut self, policy: MailboxPolicy) {
+        self.state.borrow_mut().policy = policy;
+    }
+
+    /// Number of queued messages
+    pub fn len(&self) -> usize {
+        self.state.borrow().buffer.len()
+    }
+
+    /// Priority of the next message, if any.
+    pub fn peek_priority(&self) -> Option<u32> {
+        self.state.borrow().buffer.front().map(|env| env.priority())
+    }
+
     /// Set channel capacity
     ///
     /// This method also wakes up waiting senders
@@ -264,13 +491,13 @@ impl<A> Stream for UnsyncAddrReceiver<A> where A: Actor, A::Context: AsyncContex
     type Error = ();
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        if let Some(shared) = Rc::get_mut(&mut self.state) {
+        let mut shared = self.state.borrow_mut();
+        if shared.senders == 0 {
             // All senders have been dropped, so drain the buffer and end the
             // stream.
-            return Ok(Async::Ready(shared.borrow_mut().buffer.pop_front()));
+            return Ok(Async::Ready(shared.buffer.pop_front()));
         }
 
-        let mut shared = self.state.borrow_mut();
         if let Some(msg) = shared.buffer.pop_front() {
             if let Some(task) = shared.blocked_senders.pop_front() {
                 drop(shared);
@@ -286,9 +513,16 @@ impl<A> Stream for UnsyncAddrReceiver<A> where A: Actor, A::Context: AsyncContex
 
 impl<A> Drop for UnsyncAddrReceiver<A> where A: Actor, A::Context: AsyncContext<A> {
     fn drop(&mut self) {
-        for task in &self.state.borrow().blocked_senders {
+        // notify watchers, if actor did not notify them already
+        self.terminate(&StopReason::Normal);
+
+        let shared = self.state.borrow();
+        for task in &shared.blocked_senders {
             task.notify();
         }
+        for msg in &shared.buffer {
+            report(type_name::<A>(), msg.message_type(), DeadLetterReason::Closed);
+        }
     }
 }
 
@@ -317,7 +551,7 @@ mod tests {
         let sys = System::new("test");
 
         Arbiter::handle().spawn_fn(move || {
-            let mut recv = UnsyncAddrReceiver::<Act>::new(1);
+            let mut recv = UnsyncAddrReceiver::<Act>::new(1, ActorId::next());
             assert_eq!(recv.capacity(), 1);
 
             let s1 = recv.sender();
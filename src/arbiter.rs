@@ -1,16 +1,24 @@
 use std;
 use std::thread;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use uuid::Uuid;
 use tokio_core::reactor::{Core, Handle};
 use futures::sync::oneshot::{channel, Sender};
 
 use actor::{Actor, AsyncContext};
+use clock::Clock;
+#[cfg(feature="metrics")]
+use metrics::MetricsRegistry;
+use deterministic;
+use introspect::{self, ArbiterInfo, Introspect};
+use panics::PanicLog;
+use timer;
+use watchdog::{self, WatchdogState};
 use address::{sync_channel, Addr, Syn, Unsync};
 use context::Context;
 use mailbox::DEFAULT_CAPACITY;
 use msgs::{Execute, StartActor, StopArbiter};
-use handler::Handler;
+use handler::{Handler, MessageResult};
 use registry::{Registry, SystemRegistry};
 use system::{System, RegisterArbiter, UnregisterArbiter};
 
@@ -23,7 +31,26 @@ thread_local!(
     static SYS: RefCell<Option<Addr<Syn, System>>> = RefCell::new(None);
     static SYSARB: RefCell<Option<Addr<Syn, Arbiter>>> = RefCell::new(None);
     static SYSNAME: RefCell<Option<String>> = RefCell::new(None);
-    static SYSREG: RefCell<Option<SystemRegistry>> = RefCell::new(None);
+    static SYSREG: SysRegSlot = SysRegSlot(RefCell::new(None));
+    static SYSREG_DROPPED: Cell<bool> = Cell::new(false);
+    static CLOCK: RefCell<Option<Clock>> = RefCell::new(None);
+    static PANICS: RefCell<Option<PanicLog>> = RefCell::new(None);
+    static WATCHDOG: RefCell<Option<WatchdogState>> = RefCell::new(None);
+);
+
+/// System registry of the thread, marks itself dropped on thread exit,
+/// so destructors of other thread locals do not access dropped registry
+struct SysRegSlot(RefCell<Option<SystemRegistry>>);
+
+impl Drop for SysRegSlot {
+    fn drop(&mut self) {
+        SYSREG_DROPPED.with(|dropped| dropped.set(true));
+    }
+}
+
+#[cfg(feature="metrics")]
+thread_local!(
+    static METRICS: RefCell<Option<MetricsRegistry>> = RefCell::new(None);
 );
 
 /// Event loop controller
@@ -31,9 +58,14 @@ thread_local!(
 /// Arbiter controls event loop in it's thread. Each arbiter runs in separate
 /// thread. Arbiter provides several api for event loop access. Each arbiter
 /// can belongs to specific `System` actor.
+///
+/// In deterministic system arbiters do not spawn threads, arbiter and
+/// its actors run in system's event loop.
 pub struct Arbiter {
     id: Uuid,
+    name: String,
     sys: bool,
+    local: bool,
 }
 
 impl Actor for Arbiter {
@@ -44,6 +76,13 @@ impl Actor for Arbiter {
         Arbiter::system().do_send(
             RegisterArbiter(self.id.simple().to_string(), ctx.address()));
     }
+
+    fn stopped(&mut self, _: &mut Context<Self>) {
+        if self.local {
+            Arbiter::system().do_send(
+                UnregisterArbiter(self.id.simple().to_string()));
+        }
+    }
 }
 
 impl Arbiter {
@@ -51,15 +90,25 @@ impl Arbiter {
     /// Spawn new thread and run event loop in spawned thread.
     /// Returns address of newly created arbiter.
     pub fn new<T: Into<String>>(name: T) -> Addr<Syn, Arbiter> {
-        let (tx, rx) = std::sync::mpsc::channel();
-
         let id = Uuid::new_v4();
+        let name = format!("arbiter:{:?}:{:?}",
+                           id.hyphenated().to_string(), name.into());
+        if deterministic::enabled() {
+            let (_, addr) = Actor::start(
+                Arbiter {sys: false, local: true, id: id, name: name});
+            return addr
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
         let sys = Arbiter::system();
         let sys_name = Arbiter::system_name();
         let sys_arbiter = Arbiter::system_arbiter();
         let sys_registry = Arbiter::system_registry().clone();
-        let name = format!("arbiter:{:?}:{:?}",
-                           id.hyphenated().to_string(), name.into());
+        let clock = Arbiter::clock();
+        let panics = Arbiter::panics();
+        let watchdog = Arbiter::watchdog();
+        #[cfg(feature="metrics")]
+        let metrics = Arbiter::metrics();
 
         let _ = thread::Builder::new().name(name.clone()).spawn(move|| {
             let mut core = Core::new().unwrap();
@@ -67,17 +116,25 @@ impl Arbiter {
             let (stop_tx, stop_rx) = channel();
             HND.with(|cell| *cell.borrow_mut() = Some(core.handle()));
             STOP.with(|cell| *cell.borrow_mut() = Some(stop_tx));
-            NAME.with(|cell| *cell.borrow_mut() = Some(name));
+            NAME.with(|cell| *cell.borrow_mut() = Some(name.clone()));
             REG.with(|cell| *cell.borrow_mut() = Some(Registry::new()));
 
             // system
             SYS.with(|cell| *cell.borrow_mut() = Some(sys));
             SYSARB.with(|cell| *cell.borrow_mut() = Some(sys_arbiter));
             SYSNAME.with(|cell| *cell.borrow_mut() = Some(sys_name));
-            SYSREG.with(|cell| *cell.borrow_mut() = Some(sys_registry));
+            SYSREG.with(|cell| *cell.0.borrow_mut() = Some(sys_registry));
+            CLOCK.with(|cell| *cell.borrow_mut() = Some(clock));
+            PANICS.with(|cell| *cell.borrow_mut() = Some(panics));
+            WATCHDOG.with(|cell| *cell.borrow_mut() = Some(watchdog.clone()));
+            #[cfg(feature="metrics")]
+            METRICS.with(|cell| *cell.borrow_mut() = Some(metrics));
+            timer::reset();
+            watchdog.arbiter_started(&name);
 
             // start arbiter
-            let (addr, saddr) = Actor::start(Arbiter {sys: false, id: id});
+            let (addr, saddr) = Actor::start(
+                Arbiter {sys: false, local: false, id: id, name: name});
             ADDR.with(|cell| *cell.borrow_mut() = Some(addr));
 
             if tx.send(saddr).is_err() {
@@ -98,16 +155,25 @@ impl Arbiter {
         rx.recv().unwrap()
     }
 
-    pub(crate) fn new_system(name: String) -> Core {
+    pub(crate) fn new_system(name: String, seed: Option<u64>) -> Core {
         let core = Core::new().unwrap();
         HND.with(|cell| *cell.borrow_mut() = Some(core.handle()));
         REG.with(|cell| *cell.borrow_mut() = Some(Registry::new()));
-        NAME.with(|cell| *cell.borrow_mut() = Some(name));
-        SYSREG.with(|cell| *cell.borrow_mut() = Some(SystemRegistry::new()));
+        NAME.with(|cell| *cell.borrow_mut() = Some(name.clone()));
+        SYSREG.with(|cell| *cell.0.borrow_mut() = Some(SystemRegistry::new()));
+        CLOCK.with(|cell| *cell.borrow_mut() = Some(Clock::new()));
+        PANICS.with(|cell| *cell.borrow_mut() = Some(PanicLog::new()));
+        WATCHDOG.with(|cell| *cell.borrow_mut() = Some(WatchdogState::new()));
+        #[cfg(feature="metrics")]
+        METRICS.with(|cell| *cell.borrow_mut() = Some(MetricsRegistry::new()));
+        timer::reset();
+        deterministic::reset(seed);
+        introspect::reset();
+        watchdog::reset();
 
         // start arbiter
         let (addr, sys_addr) = Actor::start(
-            Arbiter {sys: true, id: Uuid::new_v4()});
+            Arbiter {sys: true, local: false, id: Uuid::new_v4(), name: name});
         ADDR.with(|cell| *cell.borrow_mut() = Some(addr));
         SYSARB.with(|cell| *cell.borrow_mut() = Some(sys_addr));
 
@@ -161,12 +227,55 @@ impl Arbiter {
 
     /// This function returns system registry,
     pub fn system_registry() -> &'static SystemRegistry {
-        SYSREG.with(|cell| match *cell.borrow() {
+        SYSREG.with(|cell| match *cell.0.borrow() {
             Some(ref reg) => unsafe{std::mem::transmute(reg)},
             None => panic!("System is not running"),
         })
     }
 
+    /// This function returns system registry if system is running
+    /// in current thread.
+    pub(crate) fn try_system_registry() -> Option<SystemRegistry> {
+        if SYSREG_DROPPED.with(|dropped| dropped.get()) {
+            return None
+        }
+        SYSREG.with(|cell| cell.0.borrow().as_ref().cloned())
+    }
+
+    /// This function returns system clock, real time clock is used
+    /// if system is not running in current thread.
+    pub(crate) fn clock() -> Clock {
+        CLOCK.with(|cell| match *cell.borrow() {
+            Some(ref clock) => clock.clone(),
+            None => Clock::new(),
+        })
+    }
+
+    /// This function returns system's log of actor panics
+    pub(crate) fn panics() -> PanicLog {
+        PANICS.with(|cell| match *cell.borrow() {
+            Some(ref panics) => panics.clone(),
+            None => PanicLog::new(),
+        })
+    }
+
+    /// This function returns system's watchdog
+    pub(crate) fn watchdog() -> WatchdogState {
+        WATCHDOG.with(|cell| match *cell.borrow() {
+            Some(ref watchdog) => watchdog.clone(),
+            None => WatchdogState::new(),
+        })
+    }
+
+    /// This function returns system's registry of actor metrics
+    #[cfg(feature="metrics")]
+    pub(crate) fn metrics() -> MetricsRegistry {
+        METRICS.with(|cell| match *cell.borrow() {
+            Some(ref metrics) => metrics.clone(),
+            None => MetricsRegistry::new(),
+        })
+    }
+
     /// This function returns current event loop's handle,
     pub fn handle() -> &'static Handle {
         HND.with(|cell| match *cell.borrow() {
@@ -211,12 +320,14 @@ impl Arbiter {
 impl Handler<StopArbiter> for Arbiter {
     type Result = ();
 
-    fn handle(&mut self, msg: StopArbiter, _: &mut Context<Self>)
+    fn handle(&mut self, msg: StopArbiter, ctx: &mut Context<Self>)
     {
         if self.sys {
             warn!("System arbiter received `StopArbiter` message.
                   To shutdown system, `SystemExit` message should be
                   send to `Addr<Syn, System>`");
+        } else if self.local {
+            ctx.stop();
         } else {
             STOP.with(|cell| {
                 if let Some(stop) = cell.borrow_mut().take() {
@@ -235,6 +346,36 @@ impl<A> Handler<StartActor<A>> for Arbiter where A: Actor<Context=Context<A>> {
     }
 }
 
+/// Arbiter's part of system topology
+///
+/// Arbiters of deterministic system run in system arbiter's thread,
+/// their actors and services are reported by system arbiter.
+impl Handler<Introspect> for Arbiter {
+    type Result = MessageResult<Introspect>;
+
+    fn handle(&mut self, _: Introspect, _: &mut Context<Self>) -> MessageResult<Introspect> {
+        let current = thread::current();
+        let thread = match current.name() {
+            Some(name) => name.to_owned(),
+            None => format!("{:?}", current.id()),
+        };
+        let (actors, services) = if self.local {
+            // actors of the thread are listed by system arbiter
+            (introspect::actors().map(|_| Vec::new()), Vec::new())
+        } else {
+            (introspect::actors(), Arbiter::registry().services())
+        };
+        MessageResult(ArbiterInfo {
+            id: self.id.simple().to_string(),
+            name: self.name.clone(),
+            thread: thread,
+            system: self.sys,
+            actors: actors,
+            services: services,
+        })
+    }
+}
+
 /// Execute function in arbiter's thread
 impl<I: Send, E: Send> Handler<Execute<I, E>> for Arbiter {
     type Result = Result<I, E>;
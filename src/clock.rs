@@ -0,0 +1,239 @@
+//! System clock
+//!
+//! All actix timers, `AsyncContext::run_later()`, `AsyncContext::notify_later()`,
+//! interval timers, `Request::timeout()`, `ActorFuture::timeout()`,
+//! `ActorStream::timeout()` and supervisor's backoff, use system clock.
+//! By default clock follows real time. `System::test_clock()` switches clock
+//! of the current system to virtual time, which advances only with
+//! `TestClock::advance()`. Timers created before the switch keep using
+//! real time.
+//!
+//! ```rust
+//! # extern crate actix;
+//! use std::time::Duration;
+//! use actix::prelude::*;
+//! use actix::clock::TestClock;
+//!
+//! struct Retry(TestClock);
+//!
+//! impl Actor for Retry {
+//!     type Context = Context<Self>;
+//!
+//!     fn started(&mut self, ctx: &mut Self::Context) {
+//!         ctx.run_later(Duration::from_secs(30), |_, _| {
+//!             Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!         });
+//!         // fire timer without waiting 30 seconds
+//!         self.0.advance(Duration::from_secs(30));
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("test");
+//!
+//!     let _: () = Retry(System::test_clock()).start();
+//!     sys.run();
+//! }
+//! ```
+use std::io;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::{task, Async, Future, Poll};
+use futures::task::Task;
+use tokio_core::reactor::Timeout;
+
+use arbiter::Arbiter;
+
+
+/// Current time of the system clock
+pub fn now() -> Instant {
+    Arbiter::clock().now()
+}
+
+/// Shared clock of the system
+#[derive(Clone, Debug)]
+pub(crate) struct Clock(Arc<ClockInner>);
+
+#[derive(Debug)]
+struct ClockInner {
+    virt: AtomicBool,
+    state: Mutex<VirtualTime>,
+}
+
+#[derive(Debug)]
+struct VirtualTime {
+    now: Instant,
+    // pending timers ordered by deadline, keyed by deadline and timer id
+    timers: BTreeMap<(Instant, u64), Task>,
+    next_id: u64,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock(Arc::new(ClockInner {
+            virt: AtomicBool::new(false),
+            state: Mutex::new(VirtualTime{now: Instant::now(), timers: BTreeMap::new(), next_id: 0}),
+        }))
+    }
+
+    pub fn now(&self) -> Instant {
+        if self.0.virt.load(Ordering::Acquire) {
+            self.0.state.lock().unwrap().now
+        } else {
+            Instant::now()
+        }
+    }
+
+    /// Switch clock to virtual time
+    pub fn test(&self) -> TestClock {
+        let mut state = self.0.state.lock().unwrap();
+        if !self.0.virt.load(Ordering::Acquire) {
+            state.now = Instant::now();
+            self.0.virt.store(true, Ordering::Release);
+        }
+        TestClock(self.clone())
+    }
+
+    fn is_virtual(&self) -> bool {
+        self.0.virt.load(Ordering::Acquire)
+    }
+
+    fn timer_id(&self) -> u64 {
+        let mut state = self.0.state.lock().unwrap();
+        state.next_id += 1;
+        state.next_id
+    }
+
+    // Check virtual deadline, register current task if it is not reached
+    fn poll_virtual(&self, at: Instant, id: u64) -> Async<()> {
+        let mut state = self.0.state.lock().unwrap();
+        if state.now >= at {
+            state.timers.remove(&(at, id));
+            Async::Ready(())
+        } else {
+            let registered = state.timers.get(&(at, id))
+                .map(|task| task.will_notify_current()).unwrap_or(false);
+            if !registered {
+                state.timers.insert((at, id), task::current());
+            }
+            Async::NotReady
+        }
+    }
+
+    fn cancel_virtual(&self, at: Instant, id: u64) {
+        if let Ok(mut state) = self.0.state.lock() {
+            state.timers.remove(&(at, id));
+        }
+    }
+}
+
+/// Virtual clock handle, returned by `System::test_clock()`
+#[derive(Clone, Debug)]
+pub struct TestClock(Clock);
+
+impl TestClock {
+    /// Current virtual time
+    pub fn now(&self) -> Instant {
+        self.0.now()
+    }
+
+    /// Advance virtual time and fire expired timers in deadline order
+    pub fn advance(&self, dur: Duration) {
+        let expired = {
+            let mut state = (self.0).0.state.lock().unwrap();
+            state.now += dur;
+            let now = state.now;
+            let mut expired = Vec::new();
+            loop {
+                let key = match state.timers.keys().next() {
+                    Some(&key) if key.0 <= now => key,
+                    _ => break,
+                };
+                if let Some(task) = state.timers.remove(&key) {
+                    expired.push(task);
+                }
+            }
+            expired
+        };
+        for task in expired {
+            task.notify();
+        }
+    }
+}
+
+/// A future which resolves at specific point of system clock's time
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Delay {
+    inner: DelayInner,
+}
+
+#[derive(Debug)]
+enum DelayInner {
+    Real(Timeout),
+    Virtual(VirtualDelay),
+}
+
+/// Delay on virtual time, its timer is removed from the clock on drop
+#[derive(Debug)]
+struct VirtualDelay {
+    clock: Clock,
+    at: Instant,
+    id: u64,
+}
+
+impl Drop for VirtualDelay {
+    fn drop(&mut self) {
+        self.clock.cancel_virtual(self.at, self.id);
+    }
+}
+
+impl Delay {
+    /// Create delay which resolves after `dur`
+    pub fn new(dur: Duration) -> Delay {
+        let clock = Arbiter::clock();
+        let at = clock.now() + dur;
+        Delay::with_clock(clock, at)
+    }
+
+    /// Create delay which resolves at `at`
+    pub fn new_at(at: Instant) -> Delay {
+        Delay::with_clock(Arbiter::clock(), at)
+    }
+
+    fn with_clock(clock: Clock, at: Instant) -> Delay {
+        let inner = if clock.is_virtual() {
+            let id = clock.timer_id();
+            DelayInner::Virtual(VirtualDelay{clock: clock, at: at, id: id})
+        } else {
+            DelayInner::Real(Timeout::new_at(at, Arbiter::handle()).unwrap())
+        };
+        Delay{inner: inner}
+    }
+
+    /// Reset delay to resolve at `at`
+    pub fn reset(&mut self, at: Instant) {
+        match self.inner {
+            DelayInner::Real(ref mut timeout) => timeout.reset(at),
+            DelayInner::Virtual(ref mut delay) => {
+                delay.clock.cancel_virtual(delay.at, delay.id);
+                delay.at = at;
+            }
+        }
+    }
+}
+
+impl Future for Delay {
+    type Item = ();
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<(), io::Error> {
+        match self.inner {
+            DelayInner::Real(ref mut timeout) => timeout.poll(),
+            DelayInner::Virtual(ref delay) => Ok(delay.clock.poll_virtual(delay.at, delay.id)),
+        }
+    }
+}
@@ -3,10 +3,13 @@ use futures::{Future, Poll};
 use tokio_core::reactor::Handle;
 
 use fut::ActorFuture;
-use actor::{Actor, Supervised,
+use actor::{Actor, ActorId, Supervised, StopReason,
             ActorState, ActorContext, AsyncContext, SpawnHandle};
-use address::{SyncAddressReceiver, Addr, Syn, Unsync};
+use address::{SyncAddressReceiver, Addr, Destination, Syn, Unsync};
 use contextimpl::ContextImpl;
+use handler::Handler;
+use mailbox::MailboxPolicy;
+use msgs::Terminated;
 
 /// Actor execution context
 pub struct Context<A> where A: Actor<Context=Context<A>> {
@@ -19,6 +22,10 @@ impl<A> ActorContext for Context<A> where A: Actor<Context=Self> {
         self.inner.stop()
     }
     #[inline]
+    fn stop_with(&mut self, reason: StopReason) {
+        self.inner.stop_with(reason)
+    }
+    #[inline]
     fn terminate(&mut self) {
         self.inner.terminate()
     }
@@ -26,6 +33,10 @@ impl<A> ActorContext for Context<A> where A: Actor<Context=Self> {
     fn state(&self) -> ActorState {
         self.inner.state()
     }
+    #[inline]
+    fn stop_reason(&self) -> StopReason {
+        self.inner.stop_reason().clone()
+    }
 }
 
 impl<A> AsyncContext<A> for Context<A> where A: Actor<Context=Self> {
@@ -82,6 +93,80 @@ impl<A> Context<A> where A: Actor<Context=Self> {
         self.inner.set_mailbox_capacity(cap)
     }
 
+    /// Enable or disable mailbox priority mode
+    ///
+    /// In priority mode messages with higher `Message::priority()` get
+    /// delivered first, messages with equal priority are delivered in
+    /// FIFO order. Messages sent through sync address are reordered
+    /// within a window of mailbox capacity. By default priority mode
+    /// is disabled.
+    pub fn set_mailbox_priority(&mut self, priority: bool) {
+        self.inner.set_mailbox_priority(priority)
+    }
+
+    /// Set mailbox overflow policy
+    ///
+    /// Policy defines what happens with new messages if mailbox is full.
+    /// By default `MailboxPolicy::Block` is used.
+    pub fn set_mailbox_policy(&mut self, policy: MailboxPolicy) {
+        self.inner.set_mailbox_policy(policy)
+    }
+
+    /// Id of the actor
+    pub fn id(&self) -> ActorId {
+        self.inner.id()
+    }
+
+    /// Watch other actor
+    ///
+    /// Actor receives `Terminated` message when watched actor stops. If
+    /// watched actor is stopped already, `Terminated` is sent immediately.
+    /// Watching does not keep either actor alive.
+    pub fn watch<T, B>(&mut self, addr: &Addr<T, B>)
+        where A: Handler<Terminated>, T: Destination<B>
+    {
+        self.inner.handle_terminated();
+        let signals = self.inner.signals();
+        addr.watch(self.inner.id(), signals);
+    }
+
+    /// Stop watching other actor
+    pub fn unwatch<T, B>(&mut self, addr: &Addr<T, B>) where T: Destination<B> {
+        addr.unwatch(self.inner.id());
+    }
+
+    /// Link other actor
+    ///
+    /// Linked actors stop together, if one of them stops abnormally
+    /// other one stops with `StopReason::Linked` reason, unless it traps
+    /// exits, see `Context::trap_exit()`. Link is bidirectional.
+    pub fn link<B>(&mut self, addr: &Addr<Syn, B>) where B: Actor<Context=Context<B>> {
+        let id = self.inner.id();
+        let signals = self.inner.signals();
+        addr.link(id, signals);
+        self.inner.link(addr.id(), addr.signals());
+    }
+
+    /// Remove link to other actor
+    pub fn unlink<B>(&mut self, addr: &Addr<Syn, B>) where B: Actor<Context=Context<B>> {
+        let id = self.inner.id();
+        addr.unlink(id);
+        self.inner.unlink(addr.id());
+    }
+
+    /// Trap exits of linked actors
+    ///
+    /// Instead of stopping, actor receives `Terminated` message when
+    /// linked actor stops, regardless of stop reason.
+    pub fn trap_exit(&mut self) where A: Handler<Terminated> {
+        self.inner.trap_exit()
+    }
+
+    #[inline]
+    pub(crate) fn set_supervised(&mut self) {
+        self.inner.set_supervised()
+    }
+
     #[inline]
     pub(crate) fn new(act: Option<A>) -> Context<A> {
         Context { inner: ContextImpl::new(act) }
@@ -105,6 +190,16 @@ impl<A> Context<A> where A: Actor<Context=Self> {
         self.inner.restart(ctx)
     }
 
+    #[inline]
+    pub(crate) fn restartable(&self) -> bool {
+        self.inner.restartable()
+    }
+
+    #[inline]
+    pub(crate) fn supervisor_stopped(&mut self, reason: Option<StopReason>) {
+        self.inner.supervisor_stopped(reason)
+    }
+
     #[inline]
     pub(crate) fn set_actor(&mut self, act: A) {
         self.inner.set_actor(act)
@@ -126,6 +221,15 @@ impl<A> Future for Context<A> where A: Actor<Context=Self>
     }
 }
 
+impl<A> Drop for Context<A> where A: Actor<Context=Self> {
+    fn drop(&mut self) {
+        let ctx: &mut Context<A> = unsafe {
+            mem::transmute(self as &mut Context<A>)
+        };
+        self.inner.shutdown(ctx)
+    }
+}
+
 impl<A> fmt::Debug for Context<A> where A: Actor<Context=Self> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Context({:?})", self as *const _)
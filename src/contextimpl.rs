@@ -1,13 +1,24 @@
-use std::mem;
+use std::{mem, thread};
+#[cfg(feature="introspect")]
+use std::rc::Rc;
+use std::panic::{self, AssertUnwindSafe};
 
 use futures::{Async, Poll};
 use smallvec::SmallVec;
 
 use fut::ActorFuture;
-use actor::{Actor, AsyncContext, ActorState, SpawnHandle, Supervised};
-use address::{Addr, SyncAddressReceiver, Syn, Unsync};
+use actor::{Actor, ActorId, AsyncContext, ActorState, SpawnHandle, StopReason, Supervised};
+use arbiter::Arbiter;
+use address::{Addr, SignalSender, SyncAddressReceiver, Syn, Unsync};
 use contextitems::ActorWaitItem;
-use mailbox::Mailbox;
+use deterministic;
+use handler::Handler;
+#[cfg(feature="introspect")]
+use introspect::ActorStatus;
+use mailbox::{panic_message, Mailbox, MailboxPolicy, Signal};
+use msgs::Terminated;
+use utils::type_name;
+use watchdog;
 
 /// internal context state
 bitflags! {
@@ -33,6 +44,14 @@ pub struct ContextImpl<A> where A: Actor, A::Context: AsyncContext<A> {
     items: SmallVec<[Item<A>; 3]>,
     handle: SpawnHandle,
     curr_handle: SpawnHandle,
+    supervised: bool,
+    stop_reason: StopReason,
+    #[cfg(feature="introspect")]
+    status: Option<Rc<ActorStatus>>,
+    terminated: Option<fn(&mut A, Terminated, &mut A::Context)>,
+    trap_exit: bool,
+    /// cached `deterministic::enabled()`, checked on every poll
+    deterministic: bool,
 }
 
 impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
@@ -47,6 +66,13 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
             mailbox: Mailbox::default(),
             handle: SpawnHandle::default(),
             curr_handle: SpawnHandle::default(),
+            supervised: false,
+            stop_reason: StopReason::Normal,
+            #[cfg(feature="introspect")]
+            status: None,
+            terminated: None,
+            trap_exit: false,
+            deterministic: deterministic::enabled(),
         }
     }
 
@@ -60,9 +86,69 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
             mailbox: Mailbox::new(rx),
             handle: SpawnHandle::default(),
             curr_handle: SpawnHandle::default(),
+            supervised: false,
+            stop_reason: StopReason::Normal,
+            #[cfg(feature="introspect")]
+            status: None,
+            terminated: None,
+            trap_exit: false,
+            deterministic: deterministic::enabled(),
         }
     }
 
+    #[inline]
+    /// Id of the actor
+    pub fn id(&self) -> ActorId {
+        self.mailbox.id()
+    }
+
+    #[inline]
+    /// Register linked actor, see `Context::link()`
+    pub(crate) fn link(&mut self, linked: ActorId, signals: Box<SignalSender>) {
+        self.modify();
+        self.mailbox.link(linked, signals)
+    }
+
+    #[inline]
+    /// Remove linked actor
+    pub fn unlink(&mut self, linked: ActorId) {
+        self.mailbox.unlink(linked)
+    }
+
+    #[inline]
+    /// Deliver exits of linked actors as `Terminated` signals
+    pub(crate) fn trap_exit(&mut self) where A: Handler<Terminated> {
+        self.handle_terminated();
+        self.trap_exit = true;
+    }
+
+    #[inline]
+    /// Signal sender of the context, see `Context::watch()`
+    pub(crate) fn signals(&mut self) -> Box<SignalSender> {
+        self.modify();
+        self.mailbox.signals()
+    }
+
+    #[inline]
+    /// Deliver `Terminated` signals to actor's `Handler<Terminated>`
+    pub(crate) fn handle_terminated(&mut self) where A: Handler<Terminated> {
+        fn handle<A>(act: &mut A, msg: Terminated, ctx: &mut A::Context)
+            where A: Actor + Handler<Terminated>
+        {
+            let _ = Handler::handle(act, msg, ctx);
+        }
+        self.terminated = Some(handle::<A>);
+    }
+
+    #[inline]
+    /// Mark context as supervised
+    ///
+    /// Watchers of supervised actor get notified only when context is dropped,
+    /// stopped actor could be restarted by supervisor.
+    pub fn set_supervised(&mut self) {
+        self.supervised = true;
+    }
+
     #[inline]
     /// Mutable reference to an actor.
     ///
@@ -95,6 +181,21 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
         }
     }
 
+    #[inline]
+    /// Initiate stop process with specific reason
+    pub fn stop_with(&mut self, reason: StopReason) {
+        if self.flags.contains(ContextFlags::RUNNING) {
+            self.stop_reason = reason;
+            self.stop();
+        }
+    }
+
+    #[inline]
+    /// Reason of actor stop
+    pub fn stop_reason(&self) -> &StopReason {
+        &self.stop_reason
+    }
+
     #[inline]
     /// Terminate actor execution
     pub fn terminate(&mut self) {
@@ -130,6 +231,7 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
         self.handle = self.handle.next();
         let fut: Box<ActorFuture<Item=(), Error=(), Actor=A>> = Box::new(fut);
         self.items.push((self.handle, fut));
+        self.mailbox.metrics().spawned();
         self.handle
     }
 
@@ -166,6 +268,18 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
         self.mailbox.set_capacity(cap);
     }
 
+    #[inline]
+    pub fn set_mailbox_priority(&mut self, priority: bool) {
+        self.modify();
+        self.mailbox.set_priority(priority);
+    }
+
+    #[inline]
+    pub fn set_mailbox_policy(&mut self, policy: MailboxPolicy) {
+        self.modify();
+        self.mailbox.set_policy(policy);
+    }
+
     #[inline]
     pub fn unsync_address(&mut self) -> Addr<Unsync, A> {
         self.modify();
@@ -192,17 +306,26 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
         self.flags.intersects(ContextFlags::STOPPING | ContextFlags::STOPPED)
     }
 
+    /// Check if context could be restarted
+    #[inline]
+    pub fn restartable(&self) -> bool {
+        self.act.is_some() && self.mailbox.connected()
+    }
+
     /// Restart context. Cleanup all futures, except address queue.
     #[inline]
     pub fn restart(&mut self, ctx: &mut A::Context) -> bool where A: Supervised {
-        if self.act.is_none() || !self.mailbox.connected() {
+        if !self.restartable() {
             false
         } else {
             self.flags = ContextFlags::RUNNING;
             self.wait = SmallVec::new();
             self.items = SmallVec::new();
             self.handle = SpawnHandle::default();
+            self.mailbox.metrics().restarted();
+            // stop reason is available in `restarting()`
             self.actor().restarting(ctx);
+            self.stop_reason = StopReason::Normal;
             true
         }
     }
@@ -214,8 +337,47 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
     }
 
     #[inline]
-    pub fn into_inner(self) -> Option<A> {
-        self.act
+    pub fn into_inner(mut self) -> Option<A> {
+        self.act.take()
+    }
+
+    #[inline]
+    /// Notify watchers of supervised actor, actor is not going to be restarted.
+    ///
+    /// Actor's own stop reason is used if `reason` is `None`.
+    pub fn supervisor_stopped(&mut self, reason: Option<StopReason>) {
+        if let Some(reason) = reason {
+            self.stop_reason = reason;
+        }
+        self.mailbox.terminate(&self.stop_reason);
+    }
+
+    #[inline]
+    fn terminated(&mut self) {
+        if !self.supervised {
+            self.mailbox.terminate(&self.stop_reason);
+        }
+    }
+
+    /// Stop actor on system shutdown, context is dropped without being stopped.
+    ///
+    /// Actor can not prevent shutdown, result of `Actor::stopping()` is ignored.
+    pub fn shutdown(&mut self, ctx: &mut A::Context) {
+        if !self.flags.contains(ContextFlags::STARTED) ||
+            self.flags.contains(ContextFlags::STOPPED) || thread::panicking()
+        {
+            return
+        }
+        let act: &mut A = if let Some(ref mut act) = self.act {
+            unsafe { mem::transmute(act) }
+        } else {
+            return
+        };
+        self.stop_reason = StopReason::Shutdown;
+        self.flags = ContextFlags::STOPPED;
+        Actor::stopping(act, ctx);
+        Actor::stopped(act, ctx);
+        self.terminated();
     }
 
     #[inline]
@@ -224,6 +386,60 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
     }
 
     pub fn poll(&mut self, ctx: &mut A::Context) -> Poll<(), ()> {
+        let res = self.poll_actor(ctx);
+        self.update_status();
+        res
+    }
+
+    /// Publish actor's status for introspection, actor gets registered
+    /// in the arbiter it is polled in
+    #[cfg(feature="introspect")]
+    fn update_status(&mut self) {
+        if self.status.is_none() {
+            self.status = Some(ActorStatus::register::<A>(self.id()));
+        }
+        if let Some(ref status) = self.status {
+            status.update(self.state(), self.mailbox.queues(), self.items.len());
+        }
+    }
+
+    #[cfg(not(feature="introspect"))]
+    #[inline]
+    fn update_status(&mut self) {}
+
+    /// Handle context signals, returns panic message if handler panicked
+    fn poll_signals(&mut self, act: &mut A, ctx: &mut A::Context) -> Result<(), String> {
+        while !self.waiting() {
+            match self.mailbox.next_signal() {
+                Some(Signal::Terminated(msg)) => self.handle_signal(act, msg, ctx)?,
+                Some(Signal::LinkExit(id, reason)) => {
+                    self.mailbox.unlink(id);
+                    if self.trap_exit {
+                        self.handle_signal(act, Terminated{id: id, reason: reason}, ctx)?
+                    } else if !reason.is_normal() {
+                        self.stop_with(StopReason::Linked(id));
+                    }
+                }
+                Some(Signal::Stop(reason)) => self.stop_with(reason),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Pass `Terminated` signal to actor's handler
+    fn handle_signal(&self, act: &mut A, msg: Terminated, ctx: &mut A::Context)
+                     -> Result<(), String>
+    {
+        if let Some(handle) = self.terminated {
+            if let Err(err) = panic::catch_unwind(AssertUnwindSafe(|| handle(act, msg, ctx))) {
+                return Err(panic_message(&err))
+            }
+        }
+        Ok(())
+    }
+
+    fn poll_actor(&mut self, ctx: &mut A::Context) -> Poll<(), ()> {
         let act: &mut A = if let Some(ref mut act) = self.act {
             unsafe { mem::transmute(act) }
         } else {
@@ -235,6 +451,11 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
             Actor::started(act, ctx);
         }
 
+        // deterministic system reorders actors' polls
+        if self.deterministic && deterministic::yield_now() {
+            return Ok(Async::NotReady)
+        }
+
         'outer: loop {
             self.flags.remove(ContextFlags::MODIFIED);
 
@@ -243,6 +464,7 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
             // and we always have to check most recent future
             while !self.wait.is_empty() && !self.stopping() {
                 if let Some(item) = self.wait.last_mut() {
+                    let _watch = watchdog::enter::<A>(None);
                     match item.poll(act, ctx) {
                         Async::Ready(_) => (),
                         Async::NotReady => return Ok(Async::NotReady),
@@ -251,8 +473,18 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
                 self.wait.pop();
             }
 
-            // process mailbox
-            self.mailbox.poll(act, ctx);
+            // process signals and mailbox, actor stops immediately if handler panics
+            let res = self.poll_signals(act, ctx).and_then(|_| self.mailbox.poll(act, ctx));
+            if let Err(err) = res {
+                error!("Actor {} panicked: {}", type_name::<A>(), err);
+                Arbiter::panics().record(type_name::<A>(), &err);
+                self.flags = ContextFlags::STOPPED;
+                self.stop_reason = StopReason::Panicked(err);
+                Actor::stopped(act, ctx);
+                self.terminated();
+                return Ok(Async::Ready(()))
+            }
+            self.mailbox.update_metrics();
             if !self.wait.is_empty() && !self.stopping() {
                 continue
             }
@@ -261,7 +493,11 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
             let mut idx = 0;
             while idx < self.items.len() && !self.stopping() {
                 self.curr_handle = self.items[idx].0;
-                match self.items[idx].1.poll(act, ctx) {
+                let res = {
+                    let _watch = watchdog::enter::<A>(None);
+                    self.items[idx].1.poll(act, ctx)
+                };
+                match res {
                     Ok(Async::NotReady) => {
                         // item scheduled wait future
                         if !self.wait.is_empty() && !self.stopping() {
@@ -297,15 +533,22 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
             // check state
             if self.flags.contains(ContextFlags::RUNNING) {
                 // possible stop condition
-                if !self.alive() && Actor::stopping(act, ctx) {
-                    self.flags = ContextFlags::STOPPED;
-                    Actor::stopped(act, ctx);
-                    return Ok(Async::Ready(()))
+                if !self.alive() {
+                    // all addresses are dropped, reason is visible in `stopping()`
+                    let reason = mem::replace(&mut self.stop_reason, StopReason::Disconnected);
+                    if Actor::stopping(act, ctx) {
+                        self.flags = ContextFlags::STOPPED;
+                        Actor::stopped(act, ctx);
+                        self.terminated();
+                        return Ok(Async::Ready(()))
+                    }
+                    self.stop_reason = reason;
                 }
             } else if self.flags.contains(ContextFlags::STOPPING) {
                 if Actor::stopping(act, ctx) {
                     self.flags = ContextFlags::STOPPED;
                     Actor::stopped(act, ctx);
+                    self.terminated();
                     return Ok(Async::Ready(()))
                 } else {
                     self.flags.remove(ContextFlags::STOPPING);
@@ -314,6 +557,7 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
                 }
             } else if self.flags.contains(ContextFlags::STOPPED) {
                 Actor::stopped(act, ctx);
+                self.terminated();
                 return Ok(Async::Ready(()))
             }
 
@@ -321,3 +565,11 @@ impl<A> ContextImpl<A> where A: Actor, A::Context: AsyncContext<A>
         }
     }
 }
+
+impl<A> Drop for ContextImpl<A> where A: Actor, A::Context: AsyncContext<A> {
+    fn drop(&mut self) {
+        // context is dropped without being stopped, i.e. on system shutdown.
+        // watchers are notified only once, this is noop for terminated actor.
+        self.mailbox.terminate(&StopReason::Shutdown);
+    }
+}
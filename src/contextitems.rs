@@ -1,12 +1,12 @@
 use std::marker::PhantomData;
 use std::time::Duration;
-use futures::{Async, Future, Poll, Stream};
-use tokio_core::reactor::Timeout;
+use futures::{task, Async, Future, Poll, Stream};
 
 use fut::ActorFuture;
-use arbiter::Arbiter;
-use actor::{Actor, ActorContext, AsyncContext};
+use actor::{Actor, ActorContext, AsyncContext, MissedTick};
+use timer::Timer;
 use handler::{Handler, MessageResponse, Message};
+use utils::Ticker;
 
 
 pub(crate) struct ActorWaitItem<A: Actor>(Box<ActorFuture<Item=(), Error=(), Actor=A>>);
@@ -35,7 +35,7 @@ impl<A> ActorWaitItem<A> where A: Actor, A::Context: ActorContext + AsyncContext
 pub(crate)
 struct ActorDelayedMessageItem<A, M> where A: Actor, M: Message {
     msg: Option<M>,
-    timeout: Timeout,
+    timeout: Timer,
     act: PhantomData<A>,
     m: PhantomData<M>,
 }
@@ -44,7 +44,7 @@ impl<A, M> ActorDelayedMessageItem<A, M> where A: Actor, M: Message {
     pub fn new(msg: M, timeout: Duration) -> Self {
         ActorDelayedMessageItem {
             msg: Some(msg),
-            timeout: Timeout::new(timeout, Arbiter::handle()).unwrap(),
+            timeout: Timer::new(timeout),
             act: PhantomData,
             m: PhantomData,
         }
@@ -71,6 +71,52 @@ impl<A, M> ActorFuture for ActorDelayedMessageItem<A, M>
     }
 }
 
+pub(crate)
+struct ActorIntervalMessageItem<A, M, F> where A: Actor, M: Message {
+    factory: F,
+    ticker: Ticker,
+    act: PhantomData<A>,
+    m: PhantomData<M>,
+}
+
+impl<A, M, F> ActorIntervalMessageItem<A, M, F>
+    where A: Actor, M: Message, F: FnMut() -> M
+{
+    pub fn new(factory: F, interval: Duration, policy: MissedTick) -> Self {
+        ActorIntervalMessageItem {
+            factory: factory,
+            ticker: Ticker::new(interval, policy),
+            act: PhantomData,
+            m: PhantomData,
+        }
+    }
+}
+
+impl<A, M, F> ActorFuture for ActorIntervalMessageItem<A, M, F>
+    where A: Actor + Handler<M>, A::Context: AsyncContext<A>,
+          M: Message + 'static, F: FnMut() -> M,
+{
+    type Item = ();
+    type Error = ();
+    type Actor = A;
+
+    fn poll(&mut self, act: &mut A, ctx: &mut A::Context) -> Poll<Self::Item, Self::Error> {
+        loop {
+            if let Async::NotReady = self.ticker.poll() {
+                return Ok(Async::NotReady)
+            }
+            let fut = A::handle(act, (self.factory)(), ctx);
+            fut.handle::<()>(ctx, None);
+
+            // give other context items a chance to run between missed ticks
+            if !self.ticker.next() || ctx.waiting() {
+                task::current().notify();
+                return Ok(Async::NotReady)
+            }
+        }
+    }
+}
+
 pub(crate)
 struct ActorMessageItem<A, M> where A: Actor, M: Message {
     msg: Option<M>,
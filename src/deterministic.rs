@@ -0,0 +1,127 @@
+//! Seeded scheduler of deterministic system
+//!
+//! In deterministic mode all arbiters and sync actors run in the system's
+//! thread, so the only source of nondeterminism left is order in which
+//! actors get polled. Scheduler perturbs that order with seeded random
+//! generator: polled actor could yield before processing its mailbox and
+//! processes random number of messages before yielding to other actors.
+//! Same seed gives same interleaving of messages as long as actors do not
+//! depend on real time or io, use `System::test_clock()` for timers.
+use std::cell::RefCell;
+
+use futures::task;
+use uuid::Uuid;
+
+
+/// Probability of actor yielding on poll is `1 / YIELD_RATIO`
+const YIELD_RATIO: u64 = 4;
+/// Maximum number of messages processed in one poll
+const MAX_BUDGET: u64 = 8;
+
+thread_local!(
+    static SCHEDULER: RefCell<Option<Rng>> = RefCell::new(None);
+    // generator of system that is not deterministic, seeded on first use
+    static RANDOM: RefCell<Option<Rng>> = RefCell::new(None);
+);
+
+/// Switch current thread to deterministic mode if `seed` is set,
+/// i.e. on new event loop
+pub(crate) fn reset(seed: Option<u64>) {
+    SCHEDULER.with(|cell| *cell.borrow_mut() = seed.map(Rng::new));
+}
+
+/// Indicates if system of current thread is deterministic
+pub(crate) fn enabled() -> bool {
+    SCHEDULER.with(|cell| cell.borrow().is_some())
+}
+
+fn gen(range: u64) -> Option<u64> {
+    SCHEDULER.with(|cell| cell.borrow_mut().as_mut().map(|rng| rng.next() % range))
+}
+
+/// Decide if current task has to yield, task is notified and polled again
+/// after tasks that are already scheduled
+///
+/// Callers check `enabled()` once and skip this on the normal path.
+pub(crate) fn yield_now() -> bool {
+    if gen(YIELD_RATIO) == Some(0) {
+        task::current().notify();
+        true
+    } else {
+        false
+    }
+}
+
+/// Choose one of `n` alternatives, first one if system is not deterministic
+pub(crate) fn choose(n: usize) -> usize {
+    gen(n as u64).unwrap_or(0) as usize
+}
+
+/// Random number in `0..range`, drawn from scheduler's seeded generator
+/// if system is deterministic
+pub(crate) fn random(range: u64) -> u64 {
+    match gen(range) {
+        Some(n) => n,
+        None => RANDOM.with(|cell| {
+            let mut rng = cell.borrow_mut();
+            rng.get_or_insert_with(|| {
+                let bytes = Uuid::new_v4();
+                let seed = bytes.as_bytes()[..8].iter()
+                    .fold(0u64, |seed, b| (seed << 8) | u64::from(*b));
+                Rng::new(seed)
+            }).next() % range
+        }),
+    }
+}
+
+/// Number of messages actor processes before yielding
+pub(crate) struct Budget(Option<u64>);
+
+impl Budget {
+    /// Budget is unlimited unless system is deterministic, `enabled` is
+    /// cached result of `deterministic::enabled()`
+    pub fn new(enabled: bool) -> Budget {
+        if enabled {
+            Budget(gen(MAX_BUDGET).map(|n| n + 1))
+        } else {
+            Budget(None)
+        }
+    }
+
+    /// Spend budget on one message, returns `true` and notifies current task
+    /// if budget is exhausted
+    pub fn spend(&mut self) -> bool {
+        match self.0 {
+            Some(1) => {
+                task::current().notify();
+                true
+            }
+            Some(ref mut n) => {
+                *n -= 1;
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// xorshift64* generator
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // splitmix64 step, state must not be zero
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Rng(if z == 0 { 1 } else { z })
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
@@ -1,10 +1,9 @@
 use std::time::Duration;
 use futures::{Async, Future, Poll};
-use tokio_core::reactor::Timeout as TokioTimeout;
 
 use fut::ActorFuture;
 use actor::Actor;
-use arbiter::Arbiter;
+use clock::Delay;
 
 
 /// Future for the `timeout` combinator, interrupts computations if it takes more
@@ -17,7 +16,7 @@ pub struct Timeout<F> where F: ActorFuture
 {
     fut: F,
     err: Option<F::Error>,
-    timeout: TokioTimeout,
+    timeout: Delay,
 }
 
 pub fn new<F>(future: F, timeout: Duration, err: F::Error) -> Timeout<F> where F: ActorFuture
@@ -25,7 +24,7 @@ pub fn new<F>(future: F, timeout: Duration, err: F::Error) -> Timeout<F> where F
     Timeout {
         fut: future,
         err: Some(err),
-        timeout: TokioTimeout::new(timeout, Arbiter::handle()).unwrap(),
+        timeout: Delay::new(timeout),
     }
 }
 
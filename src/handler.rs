@@ -1,11 +1,10 @@
 use futures::Future;
-use futures::sync::oneshot::Sender as SyncSender;
 use futures::unsync::oneshot::Sender as UnsyncSender;
 
 use arbiter::Arbiter;
 use fut::{self, ActorFuture};
 use actor::{Actor, AsyncContext};
-use address::{Addr, Syn};
+use address::{Addr, Syn, SyncResponseSender};
 use context::Context;
 
 /// Message handler
@@ -28,6 +27,14 @@ pub trait Message {
 
     /// The type of value that this message will resolved with if it is successful.
     type Result: 'static;
+
+    /// Message priority, messages with higher priority get delivered first.
+    ///
+    /// Priority is used only if actor's mailbox runs in priority mode,
+    /// see `Context::set_mailbox_priority()`. Default priority is `0`.
+    fn priority(&self) -> u32 {
+        0
+    }
 }
 
 /// Helper type that implements `MessageResponse` trait
@@ -52,13 +59,13 @@ pub trait MessageResponse<A: Actor, M: Message> {
     fn handle<R: ResponseChannel<M>>(self, ctx: &mut A::Context, tx: Option<R>);
 }
 
-impl<M: Message + 'static> ResponseChannel<M> for SyncSender<M::Result> {
+impl<M: Message + 'static> ResponseChannel<M> for SyncResponseSender<M::Result> {
     fn is_canceled(&self) -> bool {
-        SyncSender::is_canceled(self)
+        SyncResponseSender::is_canceled(self)
     }
 
     fn send(self, response: M::Result) {
-        let _ = SyncSender::send(self, response);
+        SyncResponseSender::send(self, response);
     }
 }
 
@@ -0,0 +1,246 @@
+//! Introspection of running system
+//!
+//! `Topology` message sent to `System` actor collects arbiters of the
+//! system, actors running in each arbiter with their state, mailbox depth
+//! and number of spawned futures, and running `ArbiterService` and
+//! `SystemService` instances. Each arbiter answers `Introspect` message
+//! with its own part of the topology.
+//!
+//! `Display` implementation of `SystemInfo` renders topology as a text
+//! tree, i.e. to dump it from a signal handler of a stuck process.
+//!
+//! Actors are listed only with `introspect` cargo feature enabled, actor's
+//! context then samples its state at the end of each poll. Without the
+//! feature arbiters report services only, `ArbiterInfo::actors` is `None`
+//! and rendered topology says actors are unavailable. Sync actors do not belong to
+//! arbiters and are not listed. In deterministic system all actors run in
+//! system arbiter's thread and are listed under system arbiter.
+//!
+//! ```toml
+//! [dependencies]
+//! actix = { version = "0.5", features = ["introspect"] }
+//! ```
+//!
+//! ```rust
+//! # extern crate actix;
+//! # extern crate futures;
+//! use futures::Future;
+//! use actix::prelude::*;
+//! use actix::introspect::Topology;
+//!
+//! fn main() {
+//!     let sys = System::new("example");
+//!
+//!     Arbiter::handle().spawn(
+//!         Arbiter::system().send(Topology)
+//!             .then(|res| {
+//!                 println!("{}", res.unwrap().unwrap());
+//! #               Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!                 Ok(())
+//!             }));
+//!
+//!     sys.run();
+//! }
+//! ```
+use std::fmt;
+#[cfg(feature="introspect")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature="introspect")]
+use std::rc::{Rc, Weak};
+
+use actor::{ActorId, ActorState};
+use handler::Message;
+
+
+#[cfg(feature="introspect")]
+thread_local!(
+    static ACTORS: RefCell<Actors> = RefCell::new(Actors{statuses: Vec::new(), limit: 64});
+);
+
+#[cfg(feature="introspect")]
+struct Actors {
+    statuses: Vec<Weak<ActorStatus>>,
+    // prune statuses of dropped actors once number of statuses reaches limit
+    limit: usize,
+}
+
+/// Collect topology of the system, message is handled by `System` actor
+pub struct Topology;
+
+impl Message for Topology {
+    type Result = Result<SystemInfo, ()>;
+}
+
+/// Collect information about arbiter, message is handled by `Arbiter` actor
+pub struct Introspect;
+
+impl Message for Introspect {
+    type Result = ArbiterInfo;
+}
+
+/// Topology of the system
+#[derive(Clone, Debug)]
+pub struct SystemInfo {
+    /// Name of the system
+    pub name: String,
+    /// Running arbiters, system arbiter goes first
+    pub arbiters: Vec<ArbiterInfo>,
+    /// Type names of running system services
+    pub services: Vec<&'static str>,
+}
+
+/// Arbiter and its actors
+#[derive(Clone, Debug)]
+pub struct ArbiterInfo {
+    /// Unique id of the arbiter
+    pub id: String,
+    /// Name of the arbiter
+    pub name: String,
+    /// Name of arbiter's thread, or its id for unnamed thread
+    pub thread: String,
+    /// Indicates system arbiter
+    pub system: bool,
+    /// Actors running in the arbiter, `None` if actors are not
+    /// tracked, see `introspect` cargo feature
+    pub actors: Option<Vec<ActorInfo>>,
+    /// Type names of running arbiter services
+    pub services: Vec<&'static str>,
+}
+
+/// Actor running in an arbiter
+#[derive(Clone, Debug)]
+pub struct ActorInfo {
+    /// Id of the actor
+    pub id: ActorId,
+    /// Type name of the actor
+    pub actor: &'static str,
+    /// Execution state of the actor
+    pub state: ActorState,
+    /// Number of messages in sync queue of the mailbox
+    pub sync_queue: usize,
+    /// Number of messages in unsync queue of the mailbox
+    pub unsync_queue: usize,
+    /// Number of futures spawned in actor's context
+    pub spawned: usize,
+}
+
+impl fmt::Display for SystemInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "System {:?}", self.name)?;
+        for service in &self.services {
+            writeln!(f, "  service {}", service)?;
+        }
+        for arbiter in &self.arbiters {
+            write!(f, "{}", arbiter)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ArbiterInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "  Arbiter {:?} thread {:?}{}",
+                 self.name, self.thread, if self.system { " (system)" } else { "" })?;
+        for service in &self.services {
+            writeln!(f, "    service {}", service)?;
+        }
+        match self.actors {
+            Some(ref actors) => {
+                for actor in actors {
+                    writeln!(f, "    {}", actor)?;
+                }
+            }
+            None => writeln!(f, "    actors unavailable, enable `introspect` feature")?,
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ActorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "actor #{} {} {:?} sync_queue={} unsync_queue={} spawned={}",
+               self.id.into_usize(), self.actor, self.state,
+               self.sync_queue, self.unsync_queue, self.spawned)
+    }
+}
+
+/// Actors running in current thread, `None` if actors are not tracked
+#[cfg(feature="introspect")]
+pub(crate) fn actors() -> Option<Vec<ActorInfo>> {
+    ACTORS.with(|cell| {
+        let mut actors = cell.borrow_mut();
+        actors.statuses.retain(|status| status.upgrade().is_some());
+        Some(actors.statuses.iter()
+             .filter_map(|status| status.upgrade())
+             .map(|status| status.info())
+             .collect())
+    })
+}
+
+#[cfg(not(feature="introspect"))]
+pub(crate) fn actors() -> Option<Vec<ActorInfo>> {
+    None
+}
+
+/// Forget actors of previous event loop, i.e. on new event loop
+#[cfg(feature="introspect")]
+pub(crate) fn reset() {
+    ACTORS.with(|cell| cell.borrow_mut().statuses.clear());
+}
+
+#[cfg(not(feature="introspect"))]
+#[inline]
+pub(crate) fn reset() {}
+
+/// Status of an actor, updated by actor's context after each poll
+#[cfg(feature="introspect")]
+pub(crate) struct ActorStatus {
+    id: ActorId,
+    actor: &'static str,
+    state: Cell<ActorState>,
+    sync_queue: Cell<usize>,
+    unsync_queue: Cell<usize>,
+    spawned: Cell<usize>,
+}
+
+#[cfg(feature="introspect")]
+impl ActorStatus {
+    /// Create status of actor `A` and register it in current thread
+    pub fn register<A>(id: ActorId) -> Rc<ActorStatus> {
+        let status = Rc::new(ActorStatus {
+            id: id,
+            actor: ::utils::type_name::<A>(),
+            state: Cell::new(ActorState::Started),
+            sync_queue: Cell::new(0),
+            unsync_queue: Cell::new(0),
+            spawned: Cell::new(0),
+        });
+        ACTORS.with(|cell| {
+            let mut actors = cell.borrow_mut();
+            if actors.statuses.len() >= actors.limit {
+                actors.statuses.retain(|status| status.upgrade().is_some());
+                actors.limit = ::std::cmp::max(actors.statuses.len() * 2, 64);
+            }
+            actors.statuses.push(Rc::downgrade(&status));
+        });
+        status
+    }
+
+    pub fn update(&self, state: ActorState, queues: (usize, usize), spawned: usize) {
+        self.state.set(state);
+        self.sync_queue.set(queues.0);
+        self.unsync_queue.set(queues.1);
+        self.spawned.set(spawned);
+    }
+
+    fn info(&self) -> ActorInfo {
+        ActorInfo {
+            id: self.id,
+            actor: self.actor,
+            state: self.state.get(),
+            sync_queue: self.sync_queue.get(),
+            unsync_queue: self.unsync_queue.get(),
+            spawned: self.spawned.get(),
+        }
+    }
+}
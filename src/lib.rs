@@ -55,6 +55,9 @@ mod handler;
 mod stream;
 mod system;
 mod supervisor;
+mod timer;
+mod deterministic;
+mod panics;
 
 mod address;
 mod mailbox;
@@ -65,20 +68,28 @@ pub mod actors;
 pub mod msgs;
 pub mod sync;
 pub mod utils;
+pub mod clock;
+pub mod metrics;
+pub mod trace;
+pub mod introspect;
+pub mod watchdog;
+pub mod testkit;
 pub mod registry;
 
 pub use fut::{ActorFuture, ActorStream, WrapFuture, WrapStream, FinishStream};
-pub use actor::{Actor, ActorState, Supervised,
-                ActorContext, AsyncContext, ErrorAction, SpawnHandle};
+pub use actor::{Actor, ActorState, Supervised, ActorContext, AsyncContext,
+                ErrorAction, SpawnHandle, ActorId, StopReason, MissedTick};
 pub use handler::{Handler, Response, ActorResponse,
                   Message, MessageResult, ResponseFuture, ResponseActFuture};
 pub use arbiter::Arbiter;
-pub use address::{Addr, Syn, Unsync, ActorAddress, Recipient, MailboxError};
+pub use address::{Addr, Syn, Unsync, Mock, ActorAddress, Recipient, MailboxError,
+                  WeakAddr, WeakRecipient};
 pub use context::Context;
+pub use mailbox::MailboxPolicy;
 pub use stream::StreamHandler;
 pub use sync::{SyncContext, SyncArbiter};
 pub use system::{System, SystemRunner};
-pub use supervisor::Supervisor;
+pub use supervisor::{Supervisor, RestartPolicy, GiveUp};
 
 #[doc(hidden)]
 pub use context::ContextFutureSpawner;
@@ -113,10 +124,13 @@ pub mod prelude {
 
     pub use fut::{ActorFuture, ActorStream, WrapFuture, WrapStream};
     pub use actor::{Actor, ActorState, ActorContext, AsyncContext,
-                    ErrorAction, Supervised, SpawnHandle};
+                    ErrorAction, Supervised, SpawnHandle, ActorId, StopReason,
+                    MissedTick};
     pub use arbiter::Arbiter;
-    pub use address::{Addr, Syn, Unsync, SendError, Recipient, MailboxError};
+    pub use address::{Addr, Syn, Unsync, SendError, Recipient, MailboxError,
+                      WeakAddr, WeakRecipient};
     pub use context::{Context, ContextFutureSpawner};
+    pub use mailbox::MailboxPolicy;
     pub use registry::{ArbiterService, SystemService};
     pub use stream::StreamHandler;
     pub use handler::{Handler, Response, ActorResponse, Message, MessageResult,
@@ -152,7 +166,8 @@ pub mod dev {
 
     pub use contextimpl::ContextImpl;
     pub use handler::{MessageResponse, ResponseChannel};
-    pub use address::{ActorAddress, ToEnvelope, SyncEnvelope, Request, RecipientRequest};
+    pub use address::{ActorAddress, ToEnvelope, SyncEnvelope, SyncResponseSender,
+                      Request, RecipientRequest};
     pub use address::{Destination, MessageDestination, MessageDestinationTransport,
                       MessageRecipient, MessageRecipientTransport};
 }
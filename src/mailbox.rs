@@ -1,8 +1,17 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
 use futures::{Async, Stream};
 
-use actor::{Actor, AsyncContext};
-use address::{sync_channel, Addr, Syn, SyncAddressReceiver, Unsync, UnsyncAddrReceiver};
+use actor::{Actor, ActorId, AsyncContext, StopReason};
+use actors::dead_letters::dead_letter;
+use deterministic::{self, Budget};
+use watchdog;
+use metrics::Metrics;
+use address::{sync_channel, Addr, Recipient, Syn, SyncAddressReceiver,
+              Unsync, UnsyncAddrReceiver, SignalSender};
 use address::EnvelopeProxy;
+use msgs::{DeadLetter, DeadLetterReason, Terminated};
+use utils::type_name;
 
 /// Maximum number of consecutive polls in a loop
 const MAX_SYNC_POLLS: u32 = 256;
@@ -11,18 +20,176 @@ const MAX_SYNC_POLLS: u32 = 256;
 pub const DEFAULT_CAPACITY: usize = 16;
 
 
+/// Mailbox overflow policy
+///
+/// Policy defines what happens with a message sent with `do_send()` or
+/// `try_send()` if actor's mailbox is full. Request sent with `send()`
+/// always waits for free space in the mailbox. Messages dropped by any
+/// policy are reported to dead letters with `DeadLetterReason::Dropped`
+/// reason, rejected messages with `DeadLetterReason::Full` reason.
+#[derive(Clone)]
+pub enum MailboxPolicy {
+    /// `try_send()` fails and registers sender's task, `do_send()` ignores
+    /// mailbox capacity. This is default policy.
+    Block,
+    /// Drop new message
+    DropNewest,
+    /// Drop oldest message from the mailbox and enqueue new message.
+    /// In priority mode message with lowest priority get dropped.
+    DropOldest,
+    /// Reject new message, `do_send()` and `try_send()` fail with
+    /// `SendError::Full` error
+    Reject,
+    /// Drop new message and send its description to dead letters recipient
+    /// instead of `DeadLetters` service
+    DeadLetter(Recipient<Syn, DeadLetter>),
+}
+
+impl Default for MailboxPolicy {
+    fn default() -> Self {
+        MailboxPolicy::Block
+    }
+}
+
+/// Action for a message that does not fit into actor's mailbox
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Overflow {
+    /// Sender has to wait for free space
+    Wait,
+    /// Enqueue message regardless of capacity
+    Push,
+    /// Enqueue message and drop oldest one
+    Evict,
+    /// Drop message
+    Drop,
+    /// Return message back to sender
+    Reject,
+}
+
+impl MailboxPolicy {
+    /// Decide what to do with message `M` sent to actor `A` if mailbox is full.
+    ///
+    /// `force` indicates that message is sent with `do_send()`.
+    pub(crate) fn on_overflow<A: 'static, M: 'static>(&self, force: bool) -> Overflow {
+        match *self {
+            MailboxPolicy::Block => if force { Overflow::Push } else { Overflow::Wait },
+            MailboxPolicy::DropNewest => {
+                dead_letter::<A, M>(DeadLetterReason::Dropped);
+                Overflow::Drop
+            }
+            MailboxPolicy::DropOldest => Overflow::Evict,
+            MailboxPolicy::Reject => {
+                dead_letter::<A, M>(DeadLetterReason::Full);
+                Overflow::Reject
+            }
+            MailboxPolicy::DeadLetter(ref rcp) => {
+                let _ = rcp.do_send(DeadLetter {
+                    actor: type_name::<A>(),
+                    message: type_name::<M>(),
+                    reason: DeadLetterReason::Dropped,
+                });
+                Overflow::Drop
+            }
+        }
+    }
+}
+
+/// Signal for actor's context
+///
+/// Signals are delivered through actor's sync channel, but they are
+/// handled by the context itself rather than by actor's message handlers.
+#[doc(hidden)]
+pub enum Signal {
+    /// Watched actor is terminated, see `Context::watch()`
+    Terminated(Terminated),
+    /// Linked actor is terminated, see `Context::link()`
+    LinkExit(ActorId, StopReason),
+    /// Stop actor with specified reason
+    Stop(StopReason),
+}
+
+/// Actors watching the actor, see `Context::watch()` and `Context::link()`
+pub(crate) struct Watchers {
+    id: ActorId,
+    watchers: Vec<(ActorId, Box<SignalSender>)>,
+    links: Vec<(ActorId, Box<SignalSender>)>,
+    terminated: Option<StopReason>,
+}
+
+impl Watchers {
+    pub fn new(id: ActorId) -> Watchers {
+        Watchers{id: id, watchers: Vec::new(), links: Vec::new(), terminated: None}
+    }
+
+    /// Id of the watched actor
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
+    /// Register watcher, watcher get notified immediately
+    /// if actor is terminated already.
+    pub fn watch(&mut self, watcher: ActorId, signals: Box<SignalSender>) {
+        if let Some(ref reason) = self.terminated {
+            signals.signal(Signal::Terminated(Terminated{id: self.id, reason: reason.clone()}));
+        } else {
+            self.watchers.push((watcher, signals));
+        }
+    }
+
+    pub fn unwatch(&mut self, watcher: ActorId) {
+        self.watchers.retain(|&(id, _)| id != watcher);
+    }
+
+    /// Register linked actor, linked actor get notified immediately
+    /// if actor is terminated already.
+    pub fn link(&mut self, linked: ActorId, signals: Box<SignalSender>) {
+        if let Some(ref reason) = self.terminated {
+            signals.signal(Signal::LinkExit(self.id, reason.clone()));
+        } else if self.links.iter().all(|&(id, _)| id != linked) {
+            self.links.push((linked, signals));
+        }
+    }
+
+    pub fn unlink(&mut self, linked: ActorId) {
+        self.links.retain(|&(id, _)| id != linked);
+    }
+
+    /// Notify all watchers, only first call has effect
+    pub fn terminate(&mut self, reason: &StopReason) {
+        if self.terminated.is_some() {
+            return
+        }
+        self.terminated = Some(reason.clone());
+        for (_, signals) in self.watchers.drain(..) {
+            signals.signal(Signal::Terminated(Terminated{id: self.id, reason: reason.clone()}));
+        }
+        for (_, signals) in self.links.drain(..) {
+            signals.signal(Signal::LinkExit(self.id, reason.clone()));
+        }
+    }
+}
+
 pub(crate) struct Mailbox<A> where A: Actor, A::Context: AsyncContext<A> {
     sync_msgs: Option<SyncAddressReceiver<A>>,
     unsync_msgs: UnsyncAddrReceiver<A>,
+    priority: bool,
+    policy: MailboxPolicy,
+    metrics: Metrics,
+    deterministic: bool,
 }
 
 impl<A> Default for Mailbox<A> where A: Actor, A::Context: AsyncContext<A> {
 
     #[inline]
     fn default() -> Self {
+        let id = ActorId::next();
         Mailbox {
             sync_msgs: None,
-            unsync_msgs: UnsyncAddrReceiver::new(DEFAULT_CAPACITY) }
+            unsync_msgs: UnsyncAddrReceiver::new(DEFAULT_CAPACITY, id),
+            priority: false,
+            policy: MailboxPolicy::Block,
+            metrics: Metrics::new::<A>(id),
+            deterministic: deterministic::enabled() }
     }
 }
 
@@ -39,9 +206,39 @@ impl<A> Mailbox<A> where A: Actor, A::Context: AsyncContext<A>
 {
     #[inline]
     pub fn new(rx: SyncAddressReceiver<A>) -> Self {
+        let id = rx.id();
         Mailbox {
             sync_msgs: Some(rx),
-            unsync_msgs: UnsyncAddrReceiver::new(16) }
+            unsync_msgs: UnsyncAddrReceiver::new(DEFAULT_CAPACITY, id),
+            priority: false,
+            policy: MailboxPolicy::Block,
+            metrics: Metrics::new::<A>(id),
+            deterministic: deterministic::enabled() }
+    }
+
+    /// Id of the actor
+    pub fn id(&self) -> ActorId {
+        self.unsync_msgs.id()
+    }
+
+    /// Register linked actor, see `Context::link()`
+    pub fn link(&mut self, linked: ActorId, signals: Box<SignalSender>) {
+        if self.sync_msgs.is_none() {
+            let _ = self.remote_address();
+        }
+        self.sync_msgs.as_ref().map(|msgs| msgs.link(linked, signals));
+    }
+
+    /// Remove linked actor
+    pub fn unlink(&self, linked: ActorId) {
+        self.unsync_msgs.unlink(linked);
+        self.sync_msgs.as_ref().map(|msgs| msgs.unlink(linked));
+    }
+
+    /// Notify watchers about actor termination
+    pub fn terminate(&self, reason: &StopReason) {
+        self.unsync_msgs.terminate(reason);
+        self.sync_msgs.as_ref().map(|msgs| msgs.terminate(reason));
     }
 
     pub fn capacity(&self) -> usize {
@@ -53,15 +250,71 @@ impl<A> Mailbox<A> where A: Actor, A::Context: AsyncContext<A>
         self.sync_msgs.as_mut().map(|msgs| msgs.set_capacity(cap));
     }
     
+    /// Enable or disable priority mode
+    pub fn set_priority(&mut self, priority: bool) {
+        self.priority = priority;
+        self.unsync_msgs.set_priority(priority);
+        self.sync_msgs.as_mut().map(|msgs| msgs.set_priority(priority));
+    }
+
+    /// Set mailbox overflow policy
+    pub fn set_policy(&mut self, policy: MailboxPolicy) {
+        self.unsync_msgs.set_policy(policy.clone());
+        self.sync_msgs.as_mut().map(|msgs| msgs.set_policy(policy.clone()));
+        self.policy = policy;
+    }
+
+    /// Metrics recorder of the actor
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Depth of sync and unsync queues
+    #[cfg(any(feature="metrics", feature="introspect"))]
+    pub fn queues(&self) -> (usize, usize) {
+        (self.sync_msgs.as_ref().map(|msgs| msgs.len()).unwrap_or(0), self.unsync_msgs.len())
+    }
+
+    /// Sample depth of mailbox queues
+    #[cfg(feature="metrics")]
+    pub fn update_metrics(&self) {
+        let (sync, unsync) = self.queues();
+        self.metrics.queues(sync, unsync);
+    }
+
+    #[cfg(not(feature="metrics"))]
+    #[inline]
+    pub fn update_metrics(&self) {}
+
     #[inline]
     pub fn connected(&self) -> bool {
         self.unsync_msgs.connected() ||
             self.sync_msgs.as_ref().map(|msgs| msgs.connected()).unwrap_or(false)
     }
 
+    /// Signal sender of the context, it does not keep actor alive
+    pub fn signals(&mut self) -> Box<SignalSender> {
+        if self.sync_msgs.is_none() {
+            // signals are delivered through sync channel
+            let _ = self.remote_address();
+        }
+        match self.sync_msgs {
+            Some(ref msgs) => msgs.signals(),
+            None => unreachable!(),
+        }
+    }
+
+    /// Next signal for the context, if any
+    pub fn next_signal(&self) -> Option<Signal> {
+        self.sync_msgs.as_ref().and_then(|msgs| msgs.next_signal())
+    }
+
     pub fn remote_address(&mut self) -> Addr<Syn,A> {
         if self.sync_msgs.is_none() {
-            let (tx, rx) = sync_channel::channel(self.unsync_msgs.capacity());
+            let (tx, mut rx) = sync_channel::channel_with_id(
+                self.unsync_msgs.capacity(), self.unsync_msgs.id());
+            rx.set_priority(self.priority);
+            rx.set_policy(self.policy.clone());
             self.sync_msgs = Some(rx);
             Addr::new(tx)
         } else {
@@ -77,19 +330,29 @@ impl<A> Mailbox<A> where A: Actor, A::Context: AsyncContext<A>
         Addr::new(self.unsync_msgs.sender())
     }
 
-    pub fn poll(&mut self, act: &mut A, ctx: &mut A::Context) {
+    /// Handle incoming messages, returns panic message if message handler panicked
+    pub fn poll(&mut self, act: &mut A, ctx: &mut A::Context) -> Result<(), String> {
+        if self.priority {
+            return self.poll_priority(act, ctx)
+        }
+
+        let mut budget = Budget::new(self.deterministic);
         let mut n_polls = NumPolls(0);
         loop {
             let mut not_ready = true;
 
             // unsync messages
             loop {
-                if ctx.waiting() { return }
+                if ctx.waiting() { return Ok(()) }
 
                 match self.unsync_msgs.poll() {
                     Ok(Async::Ready(Some(mut msg))) => {
                         not_ready = false;
-                        msg.handle(act, ctx);
+                        let started = self.metrics.received();
+                        let message = msg.message_type();
+                        handle(&mut msg, message, act, ctx)?;
+                        self.metrics.handled(started, message);
+                        if budget.spend() { return Ok(()) }
                     }
                     Ok(Async::Ready(None)) | Ok(Async::NotReady) | Err(_) => break,
                 }
@@ -100,12 +363,16 @@ impl<A> Mailbox<A> where A: Actor, A::Context: AsyncContext<A>
             // sync messages
             if let Some(ref mut msgs) = self.sync_msgs {
                 loop {
-                    if ctx.waiting() { return }
+                    if ctx.waiting() { return Ok(()) }
 
                     match msgs.poll() {
                         Ok(Async::Ready(Some(mut msg))) => {
                             not_ready = false;
-                            msg.handle(act, ctx);
+                            let started = self.metrics.received();
+                            let message = msg.message_type();
+                            handle(&mut msg, message, act, ctx)?;
+                            self.metrics.handled(started, message);
+                            if budget.spend() { return Ok(()) }
                         }
                         Ok(Async::Ready(None)) | Ok(Async::NotReady) | Err(_) => break,
                     }
@@ -115,8 +382,93 @@ impl<A> Mailbox<A> where A: Actor, A::Context: AsyncContext<A>
             }
 
             if not_ready {
-                return
+                return Ok(())
+            }
+        }
+    }
+
+    /// Deliver messages from both queues in priority order
+    fn poll_priority(&mut self, act: &mut A, ctx: &mut A::Context) -> Result<(), String> {
+        let mut budget = Budget::new(self.deterministic);
+        let mut n_polls = NumPolls(0);
+        loop {
+            if ctx.waiting() { return Ok(()) }
+
+            let unsync = self.unsync_msgs.peek_priority();
+            let sync = match self.sync_msgs {
+                Some(ref mut msgs) => msgs.peek_priority(),
+                None => None,
+            };
+
+            let handled = match (unsync, sync) {
+                (Some(p1), Some(p2)) if p2 > p1 => self.handle_sync(act, ctx),
+                (Some(_), _) => self.handle_unsync(act, ctx),
+                (None, Some(_)) => self.handle_sync(act, ctx),
+                // both queues are empty, poll them to register current task
+                (None, None) => Ok(self.handle_unsync(act, ctx)? || self.handle_sync(act, ctx)?),
+            }?;
+            if !handled {
+                return Ok(())
+            }
+            if budget.spend() {
+                return Ok(())
+            }
+            debug_assert!(n_polls.inc() < MAX_SYNC_POLLS,
+                          "Use Self::Context::notify() instead of direct use of address");
+        }
+    }
+
+    fn handle_unsync(&mut self, act: &mut A, ctx: &mut A::Context) -> Result<bool, String> {
+        match self.unsync_msgs.poll() {
+            Ok(Async::Ready(Some(mut msg))) => {
+                let started = self.metrics.received();
+                let message = msg.message_type();
+                handle(&mut msg, message, act, ctx)?;
+                self.metrics.handled(started, message);
+                Ok(true)
+            }
+            Ok(Async::Ready(None)) | Ok(Async::NotReady) | Err(_) => Ok(false),
+        }
+    }
+
+    fn handle_sync(&mut self, act: &mut A, ctx: &mut A::Context) -> Result<bool, String> {
+        if let Some(ref mut msgs) = self.sync_msgs {
+            match msgs.poll() {
+                Ok(Async::Ready(Some(mut msg))) => {
+                    let started = self.metrics.received();
+                    let message = msg.message_type();
+                    handle(&mut msg, message, act, ctx)?;
+                    self.metrics.handled(started, message);
+                    Ok(true)
+                }
+                Ok(Async::Ready(None)) | Ok(Async::NotReady) | Err(_) => Ok(false),
             }
+        } else {
+            Ok(false)
         }
     }
 }
+
+/// Handle message, panic in message handler is caught and
+/// returned as error.
+fn handle<A, E>(msg: &mut E, message: &'static str, act: &mut A, ctx: &mut A::Context)
+                -> Result<(), String>
+    where A: Actor, E: EnvelopeProxy<Actor=A>
+{
+    let _watch = watchdog::enter::<A>(Some(message));
+    match panic::catch_unwind(AssertUnwindSafe(|| msg.handle(act, ctx))) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(panic_message(&err)),
+    }
+}
+
+/// Convert panic payload to string
+pub(crate) fn panic_message(err: &Box<Any + Send>) -> String {
+    if let Some(msg) = err.downcast_ref::<&'static str>() {
+        (*msg).to_owned()
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "Box<Any>".to_owned()
+    }
+}
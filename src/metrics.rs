@@ -0,0 +1,383 @@
+//! Per-actor runtime metrics
+//!
+//! With `metrics` cargo feature enabled every actor counts received and
+//! handled messages, samples depth of its mailbox queues, records handler
+//! time histogram per message type, number of spawned futures and restarts.
+//! `snapshot()` returns metrics of all running actors of the current system,
+//! `stopped()` returns counters of stopped actors accumulated per arbiter
+//! and actor type, so totals do not decrease when actors stop.
+//!
+//! Without the feature instrumentation compiles to nothing and snapshot
+//! api is not available.
+//!
+//! ```toml
+//! [dependencies]
+//! actix = { version = "0.5", features = ["metrics"] }
+//! ```
+#[cfg(feature="metrics")]
+use std::collections::HashMap;
+#[cfg(feature="metrics")]
+use std::collections::hash_map::Entry;
+#[cfg(feature="metrics")]
+use std::sync::{Arc, Mutex, Weak};
+#[cfg(feature="metrics")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature="metrics")]
+use actor::ActorId;
+#[cfg(feature="metrics")]
+use arbiter::Arbiter;
+
+
+/// Upper bounds of handler time histogram buckets, in microseconds
+#[cfg(feature="metrics")]
+const BUCKETS: [u64; 9] = [10, 50, 100, 500, 1_000, 5_000, 10_000, 100_000, 1_000_000];
+
+/// Metrics of an actor
+#[cfg(feature="metrics")]
+#[derive(Clone, Debug)]
+pub struct ActorMetrics {
+    /// Id of the actor
+    pub id: ActorId,
+    /// Type name of the actor
+    pub actor: &'static str,
+    /// Name of the arbiter actor was started in
+    pub arbiter: String,
+    /// Number of messages taken from the mailbox
+    pub received: u64,
+    /// Number of messages handled without panic
+    pub handled: u64,
+    /// Depth of sync queue at the last mailbox poll
+    pub sync_queue: usize,
+    /// Depth of unsync queue at the last mailbox poll
+    pub unsync_queue: usize,
+    /// Number of futures spawned in actor's context
+    pub spawned: u64,
+    /// Number of actor restarts
+    pub restarts: u64,
+    /// Handler time per message type
+    pub handlers: Vec<HandlerMetrics>,
+}
+
+/// Accumulated metrics of stopped actors of one type in one arbiter
+#[cfg(feature="metrics")]
+#[derive(Clone, Debug)]
+pub struct StoppedMetrics {
+    /// Type name of the actors
+    pub actor: &'static str,
+    /// Name of the arbiter actors were started in
+    pub arbiter: String,
+    /// Number of stopped actors
+    pub actors: u64,
+    /// Number of messages taken from the mailboxes
+    pub received: u64,
+    /// Number of messages handled without panic
+    pub handled: u64,
+    /// Number of futures spawned in actors' contexts
+    pub spawned: u64,
+    /// Number of actor restarts
+    pub restarts: u64,
+    /// Handler time per message type
+    pub handlers: Vec<HandlerMetrics>,
+}
+
+/// Handler time of one message type
+#[cfg(feature="metrics")]
+#[derive(Clone, Debug)]
+pub struct HandlerMetrics {
+    /// Type name of the message
+    pub message: &'static str,
+    /// Handler time histogram
+    pub time: Histogram,
+}
+
+/// Histogram of durations
+#[cfg(feature="metrics")]
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    /// Cumulative buckets, number of observations less than or equal to bound
+    pub buckets: Vec<(Duration, u64)>,
+    /// Number of observations
+    pub count: u64,
+    /// Sum of observations
+    pub sum: Duration,
+}
+
+#[cfg(feature="metrics")]
+impl Histogram {
+    fn new() -> Histogram {
+        Histogram {
+            buckets: BUCKETS.iter()
+                .map(|us| (Duration::new(us / 1_000_000, (us % 1_000_000) as u32 * 1000), 0))
+                .collect(),
+            count: 0,
+            sum: Duration::new(0, 0),
+        }
+    }
+
+    fn observe(&mut self, dur: Duration) {
+        for bucket in self.buckets.iter_mut().rev() {
+            if dur > bucket.0 {
+                break
+            }
+            bucket.1 += 1;
+        }
+        self.count += 1;
+        self.sum += dur;
+    }
+
+    /// Add observations of `other` histogram with same buckets
+    pub(crate) fn merge(&mut self, other: &Histogram) {
+        for (bucket, other) in self.buckets.iter_mut().zip(&other.buckets) {
+            bucket.1 += other.1;
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+    }
+}
+
+/// Metrics of all running actors of the current system
+#[cfg(feature="metrics")]
+pub fn snapshot() -> Vec<ActorMetrics> {
+    Arbiter::metrics().snapshot()
+}
+
+/// Metrics of specific actor
+#[cfg(feature="metrics")]
+pub fn actor(id: ActorId) -> Option<ActorMetrics> {
+    snapshot().into_iter().find(|m| m.id == id)
+}
+
+/// Accumulated metrics of stopped actors of the current system
+#[cfg(feature="metrics")]
+pub fn stopped() -> Vec<StoppedMetrics> {
+    Arbiter::metrics().stopped()
+}
+
+/// Registry of metrics of all actors of a system
+#[cfg(feature="metrics")]
+#[derive(Clone)]
+pub(crate) struct MetricsRegistry {
+    registered: Arc<Mutex<Registered>>,
+    stopped: Arc<Mutex<Stopped>>,
+}
+
+#[cfg(feature="metrics")]
+struct Registered {
+    cells: Vec<Weak<Mutex<Counters>>>,
+    // prune dropped cells once number of cells reaches limit
+    limit: usize,
+}
+
+/// Counters of stopped actors per arbiter and actor type
+#[cfg(feature="metrics")]
+type Stopped = HashMap<(String, &'static str), Totals>;
+
+#[cfg(feature="metrics")]
+#[derive(Default)]
+struct Totals {
+    actors: u64,
+    received: u64,
+    handled: u64,
+    spawned: u64,
+    restarts: u64,
+    handlers: HashMap<&'static str, Histogram>,
+}
+
+#[cfg(feature="metrics")]
+impl MetricsRegistry {
+    pub fn new() -> MetricsRegistry {
+        MetricsRegistry {
+            registered: Arc::new(Mutex::new(Registered{cells: Vec::new(), limit: 64})),
+            stopped: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn register(&self, cell: &Arc<Mutex<Counters>>) {
+        let mut reg = self.registered.lock().unwrap();
+        if reg.cells.len() >= reg.limit {
+            reg.cells.retain(|cell| cell.upgrade().is_some());
+            reg.limit = ::std::cmp::max(reg.cells.len() * 2, 64);
+        }
+        reg.cells.push(Arc::downgrade(cell));
+    }
+
+    fn snapshot(&self) -> Vec<ActorMetrics> {
+        let mut reg = self.registered.lock().unwrap();
+        reg.cells.retain(|cell| cell.upgrade().is_some());
+        reg.cells.iter()
+            .filter_map(|cell| cell.upgrade())
+            .map(|cell| cell.lock().unwrap().snapshot())
+            .collect()
+    }
+
+    fn stopped(&self) -> Vec<StoppedMetrics> {
+        let stopped = self.stopped.lock().unwrap();
+        stopped.iter()
+            .map(|(&(ref arbiter, actor), totals)| {
+                let mut handlers: Vec<_> = totals.handlers.iter()
+                    .map(|(msg, hist)| HandlerMetrics{message: *msg, time: hist.clone()})
+                    .collect();
+                handlers.sort_by_key(|h| h.message);
+
+                StoppedMetrics {
+                    actor: actor,
+                    arbiter: arbiter.clone(),
+                    actors: totals.actors,
+                    received: totals.received,
+                    handled: totals.handled,
+                    spawned: totals.spawned,
+                    restarts: totals.restarts,
+                    handlers: handlers,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature="metrics")]
+struct Counters {
+    id: ActorId,
+    actor: &'static str,
+    arbiter: String,
+    received: u64,
+    handled: u64,
+    sync_queue: usize,
+    unsync_queue: usize,
+    spawned: u64,
+    restarts: u64,
+    handlers: HashMap<&'static str, Histogram>,
+    stopped: Arc<Mutex<Stopped>>,
+}
+
+#[cfg(feature="metrics")]
+impl Counters {
+    fn snapshot(&self) -> ActorMetrics {
+        let mut handlers: Vec<_> = self.handlers.iter()
+            .map(|(msg, hist)| HandlerMetrics{message: *msg, time: hist.clone()})
+            .collect();
+        handlers.sort_by_key(|h| h.message);
+
+        ActorMetrics {
+            id: self.id,
+            actor: self.actor,
+            arbiter: self.arbiter.clone(),
+            received: self.received,
+            handled: self.handled,
+            sync_queue: self.sync_queue,
+            unsync_queue: self.unsync_queue,
+            spawned: self.spawned,
+            restarts: self.restarts,
+            handlers: handlers,
+        }
+    }
+}
+
+#[cfg(feature="metrics")]
+impl Drop for Counters {
+    // actor is stopped, fold its counters into totals of its arbiter and type
+    fn drop(&mut self) {
+        if let Ok(mut stopped) = self.stopped.lock() {
+            let totals = stopped.entry((self.arbiter.clone(), self.actor))
+                .or_insert_with(Totals::default);
+            totals.actors += 1;
+            totals.received += self.received;
+            totals.handled += self.handled;
+            totals.spawned += self.spawned;
+            totals.restarts += self.restarts;
+            for (message, hist) in self.handlers.drain() {
+                match totals.handlers.entry(message) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(hist);
+                    }
+                    Entry::Occupied(mut entry) => entry.get_mut().merge(&hist),
+                }
+            }
+        }
+    }
+}
+
+/// Start time of message handling
+#[cfg(feature="metrics")]
+pub(crate) type Started = Instant;
+#[cfg(not(feature="metrics"))]
+pub(crate) struct Started;
+
+/// Metrics recorder of an actor, noop without `metrics` feature
+#[derive(Clone)]
+pub(crate) struct Metrics {
+    #[cfg(feature="metrics")]
+    cell: Arc<Mutex<Counters>>,
+}
+
+#[cfg(feature="metrics")]
+impl Metrics {
+    /// Create recorder of actor `A` and register it in system's registry
+    pub fn new<A>(id: ActorId) -> Metrics {
+        let registry = Arbiter::metrics();
+        let cell = Arc::new(Mutex::new(Counters {
+            id: id,
+            actor: ::utils::type_name::<A>(),
+            arbiter: Arbiter::name(),
+            received: 0,
+            handled: 0,
+            sync_queue: 0,
+            unsync_queue: 0,
+            spawned: 0,
+            restarts: 0,
+            handlers: HashMap::new(),
+            stopped: Arc::clone(&registry.stopped),
+        }));
+        registry.register(&cell);
+        Metrics{cell: cell}
+    }
+
+    pub fn received(&self) -> Started {
+        self.cell.lock().unwrap().received += 1;
+        Instant::now()
+    }
+
+    pub fn handled(&self, started: Started, message: &'static str) {
+        let elapsed = started.elapsed();
+        let mut counters = self.cell.lock().unwrap();
+        counters.handled += 1;
+        counters.handlers.entry(message).or_insert_with(Histogram::new).observe(elapsed);
+    }
+
+    pub fn queues(&self, sync: usize, unsync: usize) {
+        let mut counters = self.cell.lock().unwrap();
+        counters.sync_queue = sync;
+        counters.unsync_queue = unsync;
+    }
+
+    pub fn spawned(&self) {
+        self.cell.lock().unwrap().spawned += 1;
+    }
+
+    pub fn restarted(&self) {
+        self.cell.lock().unwrap().restarts += 1;
+    }
+}
+
+#[cfg(not(feature="metrics"))]
+#[allow(unused_variables)]
+impl Metrics {
+    #[inline]
+    pub fn new<A>(id: ::actor::ActorId) -> Metrics {
+        Metrics{}
+    }
+
+    #[inline]
+    pub fn received(&self) -> Started {
+        Started
+    }
+
+    #[inline]
+    pub fn handled(&self, started: Started, message: &'static str) {}
+
+    #[inline]
+    pub fn spawned(&self) {}
+
+    #[inline]
+    pub fn restarted(&self) {}
+}
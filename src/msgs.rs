@@ -1,6 +1,6 @@
 //! Actix system messages
 
-use actor::Actor;
+use actor::{Actor, ActorId, StopReason};
 use address::{Addr, Syn};
 use context::Context;
 use handler::Message;
@@ -19,6 +19,49 @@ impl Message for StopArbiter {
     type Result = ();
 }
 
+/// Reason of message delivery failure
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeadLetterReason {
+    /// Actor's mailbox is closed
+    Closed,
+    /// Actor's mailbox is full, message is returned to sender
+    Full,
+    /// Message delivery timed out
+    Timeout,
+    /// Message got dropped by mailbox overflow policy
+    Dropped,
+}
+
+/// Description of a message that could not be delivered
+#[derive(Clone, Debug)]
+pub struct DeadLetter {
+    /// Type name of the target actor
+    pub actor: &'static str,
+    /// Type name of the message
+    pub message: &'static str,
+    /// Delivery failure reason
+    pub reason: DeadLetterReason,
+    /// Topic of the event dropped by `EventBus`
+    pub topic: Option<String>,
+}
+
+impl Message for DeadLetter {
+    type Result = ();
+}
+
+/// Watched actor is terminated, see `Context::watch()`
+#[derive(Clone, Debug)]
+pub struct Terminated {
+    /// Id of the terminated actor
+    pub id: ActorId,
+    /// Termination reason
+    pub reason: StopReason,
+}
+
+impl Message for Terminated {
+    type Result = ();
+}
+
 /// Start actor in arbiter's thread
 pub struct StartActor<A: Actor>(Box<FnBox<A>>);
 
@@ -0,0 +1,41 @@
+//! Log of panics caught in actors of the system
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+
+/// Panics caught in actors of the system, recorded only while testkit is used
+#[derive(Clone)]
+pub(crate) struct PanicLog(Arc<PanicLogInner>);
+
+struct PanicLogInner {
+    enabled: AtomicBool,
+    panics: Mutex<Vec<String>>,
+}
+
+impl PanicLog {
+    pub fn new() -> PanicLog {
+        PanicLog(Arc::new(PanicLogInner {
+            enabled: AtomicBool::new(false),
+            panics: Mutex::new(Vec::new()),
+        }))
+    }
+
+    pub fn record(&self, actor: &str, msg: &str) {
+        if self.0.enabled.load(Ordering::Relaxed) {
+            self.0.panics.lock().unwrap().push(format!("{}: {}", actor, msg));
+        }
+    }
+
+    /// Start recording panics
+    pub fn enable(&self) {
+        self.0.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Panic if any actor panicked since last check
+    pub fn check(&self) {
+        let panics: Vec<_> = self.0.panics.lock().unwrap().drain(..).collect();
+        if !panics.is_empty() {
+            panic!("Actor panicked: {}", panics.join(", "));
+        }
+    }
+}
@@ -14,6 +14,7 @@ use arbiter::Arbiter;
 use address::{Addr, Syn, Unsync};
 use context::Context;
 use supervisor::Supervisor;
+use utils::type_name;
 
 /// Actors registry
 ///
@@ -81,9 +82,12 @@ use supervisor::Supervisor;
 /// }
 /// ```
 pub struct Registry {
-    registry: RefCell<HashMap<TypeId, Box<Any>>>,
+    registry: RefCell<HashMap<TypeId, Service>>,
 }
 
+/// Registered service, type name and address
+type Service = (&'static str, Box<Any>);
+
 /// Trait defines arbiter's service.
 #[allow(unused_variables)]
 pub trait ArbiterService: Actor<Context=Context<Self>> + Supervised + Default {
@@ -121,7 +125,7 @@ impl Registry {
     /// return address of newly created actor.
     pub fn get<A: ArbiterService + Actor<Context=Context<A>>>(&self) -> Addr<Unsync, A> {
         let id = TypeId::of::<A>();
-        if let Some(addr) = self.registry.borrow().get(&id) {
+        if let Some(&(_, ref addr)) = self.registry.borrow().get(&id) {
             if let Some(addr) = addr.downcast_ref::<Addr<Unsync, A>>() {
                 return addr.clone()
             }
@@ -132,9 +136,20 @@ impl Registry {
             act
         });
 
-        self.registry.borrow_mut().insert(id, Box::new(addr.clone()));
+        self.registry.borrow_mut().insert(id, (type_name::<A>(), Box::new(addr.clone())));
         addr
     }
+
+    /// Type names of running services
+    pub(crate) fn services(&self) -> Vec<&'static str> {
+        services(self.registry.borrow().values())
+    }
+}
+
+fn services<'a, I: Iterator<Item=&'a Service>>(iter: I) -> Vec<&'static str> {
+    let mut services: Vec<_> = iter.map(|&(name, _)| name).collect();
+    services.sort();
+    services
 }
 
 // TODO: Remove lock
@@ -143,7 +158,7 @@ impl Registry {
 /// System registry serves same purpose as [Registry](struct.Registry.html), except
 /// it is shared across all arbiters.
 pub struct SystemRegistry {
-    registry: Arc<Mutex<HashMap<TypeId, Box<Any>>>>,
+    registry: Arc<Mutex<HashMap<TypeId, Service>>>,
 }
 
 unsafe impl Send for SystemRegistry {}
@@ -158,7 +173,7 @@ impl SystemRegistry {
     pub fn get<A: SystemService + Actor<Context=Context<A>>>(&self) -> Addr<Syn,A> {
         {
             if let Ok(hm) = self.registry.lock() {
-                if let Some(addr) = hm.get(&TypeId::of::<A>()) {
+                if let Some(&(_, ref addr)) = hm.get(&TypeId::of::<A>()) {
                     match addr.downcast_ref::<Addr<Syn, A>>() {
                         Some(addr) => {
                             return addr.clone()
@@ -175,11 +190,32 @@ impl SystemRegistry {
             act
         });
         if let Ok(mut hm) = self.registry.lock() {
-            hm.insert(TypeId::of::<A>(), Box::new(addr.clone()));
+            hm.insert(TypeId::of::<A>(), (type_name::<A>(), Box::new(addr.clone())));
             return addr
         }
         panic!("System registry lock is poisoned");
     }
+
+    /// Return address of the service if service actor is running.
+    /// Unlike `get()`, this method never starts new service.
+    pub fn query<A: SystemService + Actor<Context=Context<A>>>(&self) -> Option<Addr<Syn,A>> {
+        if let Ok(hm) = self.registry.lock() {
+            if let Some(&(_, ref addr)) = hm.get(&TypeId::of::<A>()) {
+                if let Some(addr) = addr.downcast_ref::<Addr<Syn, A>>() {
+                    return Some(addr.clone())
+                }
+            }
+        }
+        None
+    }
+
+    /// Type names of running services
+    pub(crate) fn services(&self) -> Vec<&'static str> {
+        match self.registry.lock() {
+            Ok(hm) => services(hm.values()),
+            Err(_) => panic!("System registry lock is poisoned"),
+        }
+    }
 }
 
 impl Clone for SystemRegistry {
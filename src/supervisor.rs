@@ -1,11 +1,124 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
 use futures::{Future, Async, Poll};
 
-use actor::{Actor, Supervised};
+use actor::{Actor, ActorContext, Supervised, StopReason};
 use arbiter::Arbiter;
+use clock::{self, Delay};
 use address::{sync_channel, ActorAddress, Addr, Syn};
 use context::Context;
+use deterministic;
 use mailbox::DEFAULT_CAPACITY;
-use msgs::Execute;
+use msgs::{Execute, StopArbiter};
+use utils::type_name;
+
+/// Number of recent restarts tracked by supervisor with unlimited restarts
+const MAX_TRACKED_RESTARTS: usize = 64;
+
+/// What supervisor does when actor exceeds restart limit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GiveUp {
+    /// Stop actor, watchers receive actor's own stop reason
+    Stop,
+    /// Stop actor with `StopReason::Escalated` reason,
+    /// linked actors stop as well
+    Escalate,
+    /// Stop actor and current arbiter with specified exit code
+    StopArbiter(i32),
+}
+
+/// Supervisor restart policy
+///
+/// By default supervisor restarts actor immediately
+/// and unlimited number of times.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use actix::{GiveUp, RestartPolicy};
+/// let policy = RestartPolicy::default()
+///     .max_restarts(5, Duration::from_secs(60))
+///     .backoff(Duration::from_millis(100), Duration::from_secs(10))
+///     .jitter(0.2)
+///     .give_up(GiveUp::Escalate);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RestartPolicy {
+    max_restarts: Option<usize>,
+    window: Duration,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    jitter: f64,
+    give_up: GiveUp,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: None,
+            window: Duration::from_secs(60),
+            min_backoff: Duration::new(0, 0),
+            max_backoff: Duration::new(0, 0),
+            jitter: 0.0,
+            give_up: GiveUp::Stop,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Allow at most `max` restarts within `window`
+    pub fn max_restarts(mut self, max: usize, window: Duration) -> Self {
+        self.max_restarts = Some(max);
+        self.window = window;
+        self
+    }
+
+    /// Wait before restart, delay starts with `min` and doubles
+    /// with each recent restart up to `max`.
+    pub fn backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = cmp::max(min, max);
+        self
+    }
+
+    /// Randomly reduce backoff delay by up to `jitter` fraction, `0.0..1.0`
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.max(0.0).min(1.0);
+        self
+    }
+
+    /// Action to take when restart limit is exceeded
+    pub fn give_up(mut self, action: GiveUp) -> Self {
+        self.give_up = action;
+        self
+    }
+
+    /// Backoff delay before restart, `restarts` is number of recent restarts
+    fn delay(&self, restarts: usize) -> Duration {
+        if self.min_backoff == Duration::new(0, 0) {
+            return self.min_backoff
+        }
+        let mut delay = self.min_backoff;
+        for _ in 0..restarts {
+            if delay >= self.max_backoff {
+                break
+            }
+            delay *= 2;
+        }
+        let delay = cmp::min(delay, self.max_backoff);
+
+        if self.jitter > 0.0 {
+            // deterministic system gets jitter from its seeded generator
+            let rnd = deterministic::random(1000) as f64 / 1000.0;
+            let ms = delay.as_secs() as f64 * 1000.0 + f64::from(delay.subsec_nanos()) / 1_000_000.0;
+            let ms = (ms * (1.0 - self.jitter * rnd)) as u64;
+            Duration::from_millis(ms)
+        } else {
+            delay
+        }
+    }
+}
 
 /// Actor supervisor
 ///
@@ -60,7 +173,10 @@ use msgs::Execute;
 /// }
 /// ```
 pub struct Supervisor<A> where A: Supervised + Actor<Context=Context<A>> {
-    ctx: A::Context
+    ctx: A::Context,
+    policy: RestartPolicy,
+    restarts: VecDeque<Instant>,
+    delay: Option<Delay>,
 }
 
 impl<A> Supervisor<A> where A: Supervised + Actor<Context=Context<A>>
@@ -92,15 +208,24 @@ impl<A> Supervisor<A> where A: Supervised + Actor<Context=Context<A>>
     pub fn start<Addr, F>(f: F) -> Addr
         where F: FnOnce(&mut A::Context) -> A + 'static,
               A: Actor<Context=Context<A>> + ActorAddress<A, Addr>
+    {
+        Supervisor::start_with(RestartPolicy::default(), f)
+    }
+
+    /// Start new supervised actor in current Arbiter with specific restart policy.
+    pub fn start_with<Addr, F>(policy: RestartPolicy, f: F) -> Addr
+        where F: FnOnce(&mut A::Context) -> A + 'static,
+              A: Actor<Context=Context<A>> + ActorAddress<A, Addr>
     {
         // create actor
         let mut ctx = Context::new(None);
+        ctx.set_supervised();
         let act = f(&mut ctx);
         let addr =  <A as ActorAddress<A, Addr>>::get(&mut ctx);
         ctx.set_actor(act);
 
         // create supervisor
-        Arbiter::handle().spawn(Supervisor::<A>{ctx: ctx});
+        Arbiter::handle().spawn(Supervisor::new(ctx, policy));
 
         addr
     }
@@ -109,19 +234,47 @@ impl<A> Supervisor<A> where A: Supervised + Actor<Context=Context<A>>
     pub fn start_in<F>(addr: &Addr<Syn, Arbiter>, f: F) -> Addr<Syn, A>
         where A: Actor<Context=Context<A>>,
               F: FnOnce(&mut Context<A>) -> A + Send + 'static
+    {
+        Supervisor::start_in_with(addr, RestartPolicy::default(), f)
+    }
+
+    /// Start new supervised actor in arbiter's thread with specific restart policy.
+    pub fn start_in_with<F>(addr: &Addr<Syn, Arbiter>, policy: RestartPolicy, f: F)
+                            -> Addr<Syn, A>
+        where A: Actor<Context=Context<A>>,
+              F: FnOnce(&mut Context<A>) -> A + Send + 'static
     {
         let (tx, rx) = sync_channel::channel(DEFAULT_CAPACITY);
 
         addr.do_send(Execute::new(move || -> Result<(), ()> {
             let mut ctx = Context::with_receiver(None, rx);
+            ctx.set_supervised();
             let act = f(&mut ctx);
             ctx.set_actor(act);
-            Arbiter::handle().spawn(Supervisor::<A>{ctx: ctx});
+            Arbiter::handle().spawn(Supervisor::new(ctx, policy));
             Ok(())
         }));
 
         Addr::new(tx)
     }
+
+    fn new(ctx: Context<A>, policy: RestartPolicy) -> Supervisor<A> {
+        Supervisor{ctx: ctx, policy: policy, restarts: VecDeque::new(), delay: None}
+    }
+
+    /// Stop supervising actor
+    fn give_up(&mut self) {
+        match self.policy.give_up {
+            GiveUp::Stop =>
+                self.ctx.supervisor_stopped(None),
+            GiveUp::Escalate =>
+                self.ctx.supervisor_stopped(Some(StopReason::Escalated)),
+            GiveUp::StopArbiter(code) => {
+                self.ctx.supervisor_stopped(None);
+                Arbiter::arbiter().do_send(StopArbiter(code));
+            }
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -131,14 +284,61 @@ impl<A> Future for Supervisor<A> where A: Supervised + Actor<Context=Context<A>>
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         loop {
+            // wait for backoff delay
+            if let Some(mut delay) = self.delay.take() {
+                match delay.poll() {
+                    Ok(Async::NotReady) => {
+                        self.delay = Some(delay);
+                        return Ok(Async::NotReady)
+                    }
+                    Ok(Async::Ready(_)) | Err(_) => {
+                        // stop if context's address is not connected
+                        if !self.ctx.restart() {
+                            self.ctx.supervisor_stopped(None);
+                            return Ok(Async::Ready(()))
+                        }
+                    }
+                }
+            }
+
             match self.ctx.poll() {
                 Ok(Async::NotReady) =>
                     return Ok(Async::NotReady),
                 Ok(Async::Ready(_)) | Err(_) => {
                     // stop if context's address is not connected
-                    if !self.ctx.restart() {
+                    if !self.ctx.restartable() {
+                        self.ctx.supervisor_stopped(None);
                         return Ok(Async::Ready(()))
                     }
+
+                    // forget restarts outside of the window
+                    let now = clock::now();
+                    while let Some(&at) = self.restarts.front() {
+                        if now.duration_since(at) < self.policy.window {
+                            break
+                        }
+                        self.restarts.pop_front();
+                    }
+
+                    // check restart limit
+                    let limit = self.policy.max_restarts.unwrap_or(MAX_TRACKED_RESTARTS);
+                    if self.restarts.len() >= limit {
+                        if self.policy.max_restarts.is_some() {
+                            self.give_up();
+                            return Ok(Async::Ready(()))
+                        }
+                        self.restarts.pop_front();
+                    }
+
+                    debug!("Restarting actor {}: {:?}",
+                           type_name::<A>(), self.ctx.stop_reason());
+                    let delay = self.policy.delay(self.restarts.len());
+                    self.restarts.push_back(now);
+                    if delay == Duration::new(0, 0) {
+                        self.ctx.restart();
+                    } else {
+                        self.delay = Some(Delay::new(delay));
+                    }
                 }
             }
         }
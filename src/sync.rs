@@ -71,27 +71,88 @@
 //!     sys.run();
 //! }
 //! ```
-use std::{mem, thread};
+use std::{cmp, mem, thread};
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::marker::PhantomData;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel as channel;
 use futures::{Async, Future, Poll, Stream};
-use futures::sync::oneshot::Sender as SyncSender;
 
-use actor::{Actor, ActorContext, ActorState};
+use actor::{Actor, ActorContext, ActorId, ActorState, StopReason};
 use arbiter::Arbiter;
 use address::sync_channel;
-use address::{Addr, Syn, SyncEnvelope, SyncAddressReceiver, EnvelopeProxy, ToEnvelope};
+use address::{Addr, Syn, SyncEnvelope, SyncResponseSender, SyncAddressReceiver,
+              EnvelopeProxy, ToEnvelope};
 use context::Context;
+use deterministic;
 use handler::{Handler, Message, MessageResponse};
+use mailbox::panic_message;
+use metrics::Metrics;
+use panics::PanicLog;
+use utils::type_name;
+
+/// Max number of worker restarts within `RESTART_WINDOW`, worker stops
+/// if actor keeps panicking outside of message handler, i.e. in factory
+const MAX_RESTARTS: usize = 10;
+
+/// Restart window in milliseconds
+const RESTART_WINDOW: u64 = 10_000;
+
+/// Max delay between worker restarts in milliseconds
+const MAX_BACKOFF: u64 = 1_000;
 
 
 /// Sync arbiter
+///
+/// In deterministic system sync arbiter does not spawn threads, actors
+/// are polled in system's thread.
 pub struct SyncArbiter<A> where A: Actor<Context=SyncContext<A>> {
     queue: channel::Sender<SyncContextProtocol<A>>,
     msgs: SyncAddressReceiver<A>,
     threads: usize,
+    workers: Vec<SyncContext<A>>,
+    limits: Vec<RestartLimit>,
+}
+
+/// Restart limit of a sync worker
+struct RestartLimit {
+    start: Instant,
+    count: usize,
+}
+
+impl RestartLimit {
+    fn new() -> RestartLimit {
+        RestartLimit{start: Instant::now(), count: 0}
+    }
+
+    /// Register restart, returns delay before next restart or `None`
+    /// if worker restarted too often
+    fn restart(&mut self) -> Option<Duration> {
+        if self.start.elapsed() > Duration::from_millis(RESTART_WINDOW) {
+            self.start = Instant::now();
+            self.count = 0;
+        }
+        self.count += 1;
+        if self.count > MAX_RESTARTS {
+            None
+        } else {
+            Some(Duration::from_millis(cmp::min(1 << self.count, MAX_BACKOFF)))
+        }
+    }
+}
+
+/// Number of sync actor restarts caused by panics
+#[derive(Clone)]
+pub struct RestartCounter(Arc<AtomicUsize>);
+
+impl RestartCounter {
+    /// Current number of restarts
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
 }
 
 impl<A> SyncArbiter<A> where A: Actor<Context=SyncContext<A>> + Send {
@@ -100,24 +161,133 @@ impl<A> SyncArbiter<A> where A: Actor<Context=SyncContext<A>> + Send {
     /// Returns address of the started actor.
     pub fn start<F>(threads: usize, factory: F) -> Addr<Syn, A>
         where F: Fn() -> A + Send + Sync + 'static
+    {
+        SyncArbiter::start_with_counter(threads, factory).0
+    }
+
+    /// Start new sync arbiter with specified number of worker threads.
+    /// Returns address of the started actor and restart counter.
+    ///
+    /// If actor panics, sender of the message gets `MailboxError::Panicked`
+    /// error and worker creates new actor instance with `factory`.
+    pub fn start_with_counter<F>(threads: usize, factory: F) -> (Addr<Syn, A>, RestartCounter)
+        where F: Fn() -> A + Send + Sync + 'static
     {
         let factory = Arc::new(factory);
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let panics = Arbiter::panics();
         let (sender, receiver) = channel::unbounded();
 
+        if deterministic::enabled() {
+            let workers = (0..threads).map(|_| SyncContext::new(
+                Arc::clone(&factory), receiver.clone(), Arc::clone(&restarts),
+                panics.clone(), Metrics::new::<A>(ActorId::next()))).collect();
+            let limits = (0..threads).map(|_| RestartLimit::new()).collect();
+
+            let (tx, rx) = sync_channel::channel(0);
+            Arbiter::handle().spawn(
+                SyncArbiter{queue: sender, msgs: rx, threads: 0,
+                            workers: workers, limits: limits});
+
+            return (Addr::new(tx), RestartCounter(restarts))
+        }
+
+        let running = Arc::new(AtomicUsize::new(threads));
         for _ in 0..threads {
             let f = Arc::clone(&factory);
+            let running = Arc::clone(&running);
             let actor_queue = receiver.clone();
+            let restarts = Arc::clone(&restarts);
+            let panics = panics.clone();
+            let metrics = Metrics::new::<A>(ActorId::next());
 
             thread::spawn(move || {
-                SyncContext::new(f, actor_queue).run()
+                // respawn worker if actor panics outside of message handler,
+                // worker stops if actor restarts too often
+                let mut limit = RestartLimit::new();
+                loop {
+                    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                        SyncContext::new(Arc::clone(&f), actor_queue.clone(),
+                                         Arc::clone(&restarts), panics.clone(),
+                                         metrics.clone()).run()
+                    }));
+                    match res {
+                        Ok(()) => break,
+                        Err(err) => {
+                            let err = panic_message(&err);
+                            error!("Sync actor {} panicked: {}", type_name::<A>(), err);
+                            panics.record(type_name::<A>(), &err);
+                            restarts.fetch_add(1, Ordering::Relaxed);
+                            metrics.restarted();
+
+                            match limit.restart() {
+                                Some(delay) => thread::sleep(delay),
+                                None => {
+                                    error!("Sync actor {} restarted too often, stopping worker",
+                                           type_name::<A>());
+                                    // last worker fails pending messages with
+                                    // `MailboxError::Closed`
+                                    if running.fetch_sub(1, Ordering::SeqCst) == 1 {
+                                        while let Ok(_) = actor_queue.try_recv() {}
+                                    }
+                                    break
+                                }
+                            }
+                        }
+                    }
+                }
             });
         }
 
         let (tx, rx) = sync_channel::channel(0);
         Arbiter::handle().spawn(
-            SyncArbiter{queue: sender, msgs: rx, threads: threads});
+            SyncArbiter{queue: sender, msgs: rx, threads: threads,
+                        workers: Vec::new(), limits: Vec::new()});
+
+        (Addr::new(tx), RestartCounter(restarts))
+    }
+}
 
-        Addr::new(tx)
+impl<A> SyncArbiter<A> where A: Actor<Context=SyncContext<A>> {
+    // Process message by one of local workers, worker is recreated
+    // if actor panics outside of message handler. Returns `false` if
+    // actor restarted too often and worker is removed.
+    fn dispatch(&mut self, idx: usize, msg: SyncContextProtocol<A>) -> bool {
+        let mut res = {
+            let worker = &mut self.workers[idx];
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                if worker.state == ActorState::Started {
+                    worker.started();
+                }
+                worker.process(msg);
+            })).map(|_| None)
+        };
+        loop {
+            let err = match res {
+                Ok(Some(worker)) => {
+                    self.workers[idx] = worker;
+                    return true
+                },
+                Ok(None) => return true,
+                Err(err) => err,
+            };
+            self.workers[idx].panicked(&panic_message(&err));
+
+            if self.limits[idx].restart().is_none() {
+                error!("Sync actor {} restarted too often, stopping worker", type_name::<A>());
+                self.workers.swap_remove(idx);
+                self.limits.swap_remove(idx);
+                return false
+            }
+            res = {
+                let w = &self.workers[idx];
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    Some(SyncContext::new(Arc::clone(&w.factory), w.queue.clone(),
+                                          Arc::clone(&w.restarts), w.panics.clone(),
+                                          w.metrics.clone()))
+                }))
+            };
+        }
     }
 }
 
@@ -134,9 +304,17 @@ impl<A> Future for SyncArbiter<A> where A: Actor<Context=SyncContext<A>>
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         loop {
             match self.msgs.poll() {
-                Ok(Async::Ready(Some(msg))) =>
-                    self.queue.send(SyncContextProtocol::Envelope(msg))
-                    .expect("Should not fail"),
+                Ok(Async::Ready(Some(msg))) => if self.workers.is_empty() {
+                    if self.queue.send(SyncContextProtocol::Envelope(msg)).is_err() {
+                        // all workers are stopped, pending and new messages
+                        // fail with `MailboxError::Closed`
+                        self.msgs.terminate(&StopReason::Escalated);
+                        return Ok(Async::Ready(()))
+                    }
+                } else {
+                    let idx = deterministic::choose(self.workers.len());
+                    self.dispatch(idx, SyncContextProtocol::Envelope(msg));
+                },
                 Ok(Async::NotReady) => break,
                 Ok(Async::Ready(None)) | Err(_) => unreachable!(),
             }
@@ -150,6 +328,12 @@ impl<A> Future for SyncArbiter<A> where A: Actor<Context=SyncContext<A>>
             for _ in 0..self.threads {
                 let _ = self.queue.send(SyncContextProtocol::Stop);
             }
+            let mut idx = 0;
+            while idx < self.workers.len() {
+                if self.dispatch(idx, SyncContextProtocol::Stop) {
+                    idx += 1;
+                }
+            }
             Ok(Async::Ready(()))
         }
     }
@@ -159,8 +343,10 @@ impl<A, M> ToEnvelope<Syn, A, M> for SyncContext<A>
     where A: Actor<Context=SyncContext<A>> + Handler<M>,
           M: Message + Send + 'static, M::Result: Send,
 {
-    fn pack(msg: M, tx: Option<SyncSender<M::Result>>) -> SyncEnvelope<A> {
-        SyncEnvelope::with_proxy(Box::new(SyncContextEnvelope::new(msg, tx)))
+    fn pack(msg: M, tx: Option<SyncResponseSender<M::Result>>) -> SyncEnvelope<A> {
+        let mut env = SyncEnvelope::with_proxy(Box::new(SyncContextEnvelope::new(msg, tx)));
+        env.set_message_type(type_name::<M>());
+        env
     }
 }
 
@@ -175,62 +361,113 @@ pub struct SyncContext<A> where A: Actor<Context=SyncContext<A>> {
     queue: channel::Receiver<SyncContextProtocol<A>>,
     stopping: bool,
     state: ActorState,
+    stop_reason: StopReason,
     factory: Arc<Fn() -> A>,
+    restarts: Arc<AtomicUsize>,
+    panics: PanicLog,
+    metrics: Metrics,
 }
 
 impl<A> SyncContext<A> where A: Actor<Context=Self> {
     /// Create new SyncContext
-    fn new(factory: Arc<Fn() -> A>, queue: channel::Receiver<SyncContextProtocol<A>>) -> Self {
+    fn new(factory: Arc<Fn() -> A>, queue: channel::Receiver<SyncContextProtocol<A>>,
+           restarts: Arc<AtomicUsize>, panics: PanicLog, metrics: Metrics) -> Self {
         SyncContext {
             act: factory(),
             queue: queue,
             stopping: false,
             state: ActorState::Started,
+            stop_reason: StopReason::Normal,
             factory: factory,
+            restarts: restarts,
+            panics: panics,
+            metrics: metrics,
         }
     }
 
     fn run(&mut self) {
+        self.started();
+
+        loop {
+            if let Ok(msg) = self.queue.recv() {
+                if !self.process(msg) {
+                    return
+                }
+            }
+        }
+    }
+
+    // Report panic of the actor, actor is going to be restarted
+    fn panicked(&self, err: &str) {
+        error!("Sync actor {} panicked: {}", type_name::<A>(), err);
+        self.panics.record(type_name::<A>(), err);
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+        self.metrics.restarted();
+    }
+
+    fn started(&mut self) {
         let ctx: &mut SyncContext<A> = unsafe {
             mem::transmute(self as &mut SyncContext<A>)
         };
-
-        // started
         A::started(&mut self.act, ctx);
         self.state = ActorState::Running;
+    }
 
-        loop {
-            match self.queue.recv() {
-                Ok(SyncContextProtocol::Stop) => {
-                    self.state = ActorState::Stopping;
-                    if !A::stopping(&mut self.act, ctx) {
-                        warn!("stopping method is not supported for sync actors");
-                    }
-                    self.state = ActorState::Stopped;
-                    A::stopped(&mut self.act, ctx);
-                    return
-                },
-                Ok(SyncContextProtocol::Envelope(mut env)) => {
-                    env.handle(&mut self.act, ctx);
-                },
-                Err(_) => (),
-            }
-
-            if self.stopping {
-                self.stopping = false;
+    // Handle protocol message, returns `false` if actor has stopped
+    fn process(&mut self, msg: SyncContextProtocol<A>) -> bool {
+        let ctx: &mut SyncContext<A> = unsafe {
+            mem::transmute(self as &mut SyncContext<A>)
+        };
 
-                // stop old actor
-                A::stopping(&mut self.act, ctx);
+        match msg {
+            SyncContextProtocol::Stop => {
+                self.state = ActorState::Stopping;
+                self.stop_reason = StopReason::Disconnected;
+                if !A::stopping(&mut self.act, ctx) {
+                    warn!("stopping method is not supported for sync actors");
+                }
                 self.state = ActorState::Stopped;
                 A::stopped(&mut self.act, ctx);
+                return false
+            },
+            SyncContextProtocol::Envelope(mut env) => {
+                let started = self.metrics.received();
+                let res = {
+                    let act = &mut self.act;
+                    panic::catch_unwind(AssertUnwindSafe(|| env.handle(act, ctx)))
+                };
+                match res {
+                    Ok(()) => self.metrics.handled(started, env.message_type()),
+                    Err(err) => {
+                        self.panicked(&panic_message(&err));
+
+                        // actor's state could be inconsistent, start new actor
+                        self.state = ActorState::Started;
+                        self.stop_reason = StopReason::Normal;
+                        self.act = (*self.factory)();
+                        A::started(&mut self.act, ctx);
+                        self.state = ActorState::Running;
+                    }
+                }
+            },
+        }
 
-                // start new actor
-                self.state = ActorState::Started;
-                self.act = (*self.factory)();
-                A::started(&mut self.act, ctx);
-                self.state = ActorState::Running;
-            }
+        if self.stopping {
+            self.stopping = false;
+
+            // stop old actor
+            A::stopping(&mut self.act, ctx);
+            self.state = ActorState::Stopped;
+            A::stopped(&mut self.act, ctx);
+
+            // start new actor
+            self.state = ActorState::Started;
+            self.stop_reason = StopReason::Normal;
+            self.act = (*self.factory)();
+            A::started(&mut self.act, ctx);
+            self.state = ActorState::Running;
         }
+        true
     }
 }
 
@@ -252,13 +489,25 @@ impl<A> ActorContext for SyncContext<A> where A: Actor<Context=Self>
     fn state(&self) -> ActorState {
         self.state
     }
+
+    /// Stop current actor with specific reason.
+    /// SyncContext creates and starts new actor.
+    fn stop_with(&mut self, reason: StopReason) {
+        self.stop_reason = reason;
+        self.stop();
+    }
+
+    /// Reason of current actor stop
+    fn stop_reason(&self) -> StopReason {
+        self.stop_reason.clone()
+    }
 }
 
 pub(crate) struct SyncContextEnvelope<A, M>
     where A: Actor<Context=SyncContext<A>> + Handler<M>, M: Message + Send,
 {
     msg: Option<M>,
-    tx: Option<SyncSender<M::Result>>,
+    tx: Option<SyncResponseSender<M::Result>>,
     actor: PhantomData<A>,
 }
 
@@ -269,7 +518,7 @@ impl<A, M> SyncContextEnvelope<A, M>
     where A: Actor<Context=SyncContext<A>> + Handler<M>,
           M: Message + Send, M::Result: Send
 {
-    pub fn new(msg: M, tx: Option<SyncSender<M::Result>>) -> Self {
+    pub fn new(msg: M, tx: Option<SyncResponseSender<M::Result>>) -> Self {
         SyncContextEnvelope{msg: Some(msg),
                             tx: tx,
                             actor: PhantomData}
@@ -1,13 +1,15 @@
 use std::collections::HashMap;
 use tokio_core::reactor::{Core, Handle};
-use futures::Future;
+use futures::{future, Future};
 use futures::sync::oneshot::{channel, Receiver, Sender};
 
 use actor::Actor;
 use address::{Addr, Syn};
 use arbiter::Arbiter;
-use handler::{Handler, Message};
+use clock::TestClock;
+use handler::{Handler, Message, ResponseFuture};
 use context::Context;
+use introspect::{Introspect, SystemInfo, Topology};
 use msgs::{SystemExit, StopArbiter};
 
 /// System is an actor which manages process.
@@ -64,8 +66,45 @@ impl System {
     #[cfg_attr(feature="cargo-clippy", allow(new_ret_no_self))]
     /// Create new system
     pub fn new<T: Into<String>>(name: T) -> SystemRunner {
-        let name = name.into();
-        let core = Arbiter::new_system(name.clone());
+        System::with_seed(name.into(), None)
+    }
+
+    /// Create new deterministic system
+    ///
+    /// All arbiters and `SyncArbiter` workers run in current thread.
+    /// Order in which actors process their mailboxes is chosen by random
+    /// generator seeded with `seed`, so run of the system could be
+    /// replayed with the same seed, i.e. to reproduce a bug that depends
+    /// on interleaving of messages. Real time timers and io are not
+    /// deterministic, use `System::test_clock()` for timers.
+    ///
+    /// Arbiters of deterministic system share registry and thread locals,
+    /// `Arbiter::name()` and `Arbiter::arbiter()` return system arbiter,
+    /// actors started in an arbiter keep running after arbiter stops.
+    ///
+    /// ```rust
+    /// # extern crate actix;
+    /// use actix::prelude::*;
+    ///
+    /// fn main() {
+    ///     let sys = System::deterministic(42);
+    ///
+    ///     // closure runs in the system's thread
+    ///     Arbiter::new("worker").do_send(actix::msgs::Execute::new(|| -> Result<(), ()> {
+    ///         Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    ///         Ok(())
+    ///     }));
+    ///
+    ///     sys.run();
+    /// }
+    /// ```
+    pub fn deterministic(seed: u64) -> SystemRunner {
+        info!("Starting deterministic system with seed {}", seed);
+        System::with_seed("deterministic".to_owned(), Some(seed))
+    }
+
+    fn with_seed(name: String, seed: Option<u64>) -> SystemRunner {
+        let core = Arbiter::new_system(name.clone(), seed);
         let (stop_tx, stop_rx) = channel();
 
         // start system
@@ -78,6 +117,15 @@ impl System {
             stop: stop_rx,
         }
     }
+
+    /// Switch clock of the current system to virtual time and
+    /// return test clock handle.
+    ///
+    /// Virtual time advances only with `TestClock::advance()`, timers
+    /// created before the switch keep using real time.
+    pub fn test_clock() -> TestClock {
+        Arbiter::clock().test()
+    }
 }
 
 /// Helper object that runs System's event loop
@@ -163,3 +211,28 @@ impl Handler<UnregisterArbiter> for System {
         self.arbiters.remove(&msg.0);
     }
 }
+
+/// Collect topology from all registered arbiters
+impl Handler<Topology> for System {
+    type Result = ResponseFuture<SystemInfo, ()>;
+
+    fn handle(&mut self, _: Topology, _: &mut Context<Self>) -> Self::Result {
+        let name = Arbiter::system_name();
+        let services = Arbiter::system_registry().services();
+
+        // arbiter that stops in the meantime is skipped
+        let arbiters: Vec<_> = self.arbiters.values()
+            .map(|addr| addr.send(Introspect).then(|res| Ok::<_, ()>(res.ok())))
+            .collect();
+
+        Box::new(future::join_all(arbiters).map(move |arbiters| {
+            let mut arbiters: Vec<_> = arbiters.into_iter().filter_map(|info| info).collect();
+            arbiters.sort_by_key(|info| (!info.system, info.name.clone()));
+            SystemInfo {
+                name: name,
+                arbiters: arbiters,
+                services: services,
+            }
+        }))
+    }
+}
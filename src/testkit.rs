@@ -0,0 +1,169 @@
+//! Helpers for testing actors
+//!
+//! `TestProbe<M>` is an actor that records messages of type `M`, its
+//! recipient could be passed to actors under test. Expectations run
+//! system's event loop until message is received or timeout elapses and
+//! panic if expectation is not met. `run_until()` runs system until
+//! future resolves. Timeouts follow system clock, with
+//! `System::test_clock()` they elapse only when virtual time advances.
+//!
+//! Panics in actors' message handlers are caught by actix, actor stops and
+//! system keeps running. Testkit reports such panics, all helpers panic
+//! if any actor of the system panicked.
+//!
+//! `MockRecipient<M>` records messages sent to `Recipient<Mock, M>` and
+//! responds without running an actor.
+//!
+//! ```rust
+//! # extern crate actix;
+//! use std::time::Duration;
+//! use actix::prelude::*;
+//! use actix::testkit::TestProbe;
+//!
+//! struct Ping(usize);
+//!
+//! impl Message for Ping {
+//!     type Result = ();
+//! }
+//!
+//! struct Pinger(Recipient<Syn, Ping>);
+//!
+//! impl Actor for Pinger {
+//!     type Context = Context<Self>;
+//!
+//!     fn started(&mut self, _: &mut Self::Context) {
+//!         let _ = self.0.do_send(Ping(1));
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut sys = System::new("test");
+//!     let mut probe = TestProbe::new();
+//!
+//!     let _: () = Pinger(probe.recipient()).start();
+//!
+//!     assert_eq!(probe.expect_msg(&mut sys).0, 1);
+//!     probe.expect_no_msg(&mut sys, Duration::from_millis(50));
+//! }
+//! ```
+use std::time::Duration;
+
+use futures::{Future, Stream};
+use futures::future::Either;
+use futures::unsync::mpsc::{unbounded, UnboundedSender, UnboundedReceiver};
+
+use clock::Delay;
+use prelude::*;
+use system::SystemRunner;
+use utils::type_name;
+
+pub use address::MockRecipient;
+pub(crate) use panics::PanicLog;
+
+
+/// Default timeout of `TestProbe` expectations
+const DEFAULT_TIMEOUT: u64 = 3;
+
+// Run event loop until future resolves, `None` if timeout elapses
+fn run<F: Future>(sys: &mut SystemRunner, fut: F, timeout: Duration)
+                  -> Option<Result<F::Item, F::Error>>
+{
+    let panics: PanicLog = Arbiter::panics();
+    panics.enable();
+
+    let timeout = Delay::new(timeout);
+    let res = match sys.run_until_complete(fut.select2(timeout)) {
+        Ok(Either::A((item, _))) => Some(Ok(item)),
+        Err(Either::A((err, _))) => Some(Err(err)),
+        Ok(Either::B(_)) | Err(Either::B(_)) => None,
+    };
+    panics.check();
+    res
+}
+
+/// Run system until `fut` resolves
+///
+/// Panics if future does not resolve within `timeout` or
+/// if any actor panics.
+pub fn run_until<F: Future>(sys: &mut SystemRunner, fut: F, timeout: Duration)
+                            -> Result<F::Item, F::Error>
+{
+    match run(sys, fut, timeout) {
+        Some(res) => res,
+        None => panic!("Future did not resolve within {:?}", timeout),
+    }
+}
+
+/// Actor that records received messages
+pub struct TestProbe<M> where M: Message + Send + 'static, M::Result: Send + Default {
+    addr: Addr<Syn, Probe<M>>,
+    rx: UnboundedReceiver<M>,
+    timeout: Duration,
+}
+
+impl<M> TestProbe<M> where M: Message + Send + 'static, M::Result: Send + Default {
+    /// Start probe in current arbiter
+    #[cfg_attr(feature="cargo-clippy", allow(new_without_default))]
+    pub fn new() -> TestProbe<M> {
+        let (tx, rx) = unbounded();
+        TestProbe {
+            addr: Probe(tx).start(),
+            rx: rx,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+        }
+    }
+
+    /// Set timeout of `expect_msg()` and `receive_n()`, default is 3 seconds
+    pub fn timeout(mut self, timeout: Duration) -> TestProbe<M> {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Probe's recipient, probe responds with `M::Result::default()`
+    pub fn recipient(&self) -> Recipient<Syn, M> {
+        self.addr.clone().recipient()
+    }
+
+    /// Wait for next message
+    pub fn expect_msg(&mut self, sys: &mut SystemRunner) -> M {
+        let timeout = self.timeout;
+        match run(sys, (&mut self.rx).into_future(), timeout) {
+            Some(Ok((Some(msg), _))) => msg,
+            Some(_) => unreachable!(),
+            None => panic!("Message {} is not received within {:?}", type_name::<M>(), timeout),
+        }
+    }
+
+    /// Check that no message is received within `within`
+    pub fn expect_no_msg(&mut self, sys: &mut SystemRunner, within: Duration) {
+        if let Some(Ok((Some(_), _))) = run(sys, (&mut self.rx).into_future(), within) {
+            panic!("Unexpected message {} is received", type_name::<M>());
+        }
+    }
+
+    /// Wait for next `n` messages
+    pub fn receive_n(&mut self, sys: &mut SystemRunner, n: usize) -> Vec<M> {
+        let timeout = self.timeout;
+        match run(sys, (&mut self.rx).take(n as u64).collect(), timeout) {
+            Some(Ok(msgs)) => msgs,
+            Some(Err(_)) => unreachable!(),
+            None => panic!("{} messages {} are not received within {:?}",
+                           n, type_name::<M>(), timeout),
+        }
+    }
+}
+
+struct Probe<M>(UnboundedSender<M>);
+
+impl<M: 'static> Actor for Probe<M> {
+    type Context = Context<Self>;
+}
+
+impl<M> Handler<M> for Probe<M> where M: Message + 'static, M::Result: Default {
+    type Result = MessageResult<M>;
+
+    fn handle(&mut self, msg: M, _: &mut Self::Context) -> Self::Result {
+        let _ = self.0.unbounded_send(msg);
+        MessageResult(Default::default())
+    }
+}
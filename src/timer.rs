@@ -0,0 +1,364 @@
+//! Arbiter-local hierarchical timer wheel
+//!
+//! Context timers, `run_later()`, `notify_later()` and interval timers, are
+//! registered in a timer wheel of the current arbiter instead of allocating
+//! reactor timeout per timer. Wheel has `LEVELS` levels of `SLOTS` slots,
+//! slot of level 0 is one millisecond, slot of each next level covers whole
+//! previous level. Insertion and cancellation are O(1), all timers of a slot
+//! fire in one batch. Wheel is driven by single `Delay` set to the earliest
+//! occupied slot, so wheel follows system clock, including virtual time.
+//! Tasks of a fired batch are notified in deadline order, so actors that
+//! own expired timers get polled in order of their deadlines.
+use std::{cmp, mem};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+use futures::{task, Async, Future, Poll};
+use futures::task::Task;
+
+use arbiter::Arbiter;
+use clock::{self, Delay};
+
+
+const BITS: usize = 6;
+const SLOTS: usize = 1 << BITS;
+const LEVELS: usize = 6;
+/// Slot for timers beyond the last level
+const OVERFLOW: usize = LEVELS * SLOTS;
+
+thread_local!(
+    static WHEEL: RefCell<Option<Rc<RefCell<Wheel>>>> = RefCell::new(None);
+);
+
+/// Drop timer wheel of the current thread, i.e. on new event loop
+pub(crate) fn reset() {
+    WHEEL.with(|cell| *cell.borrow_mut() = None);
+}
+
+// Timer wheel of the current arbiter, wheel's driver is spawned
+// on first use
+fn current() -> Rc<RefCell<Wheel>> {
+    WHEEL.with(|cell| {
+        if let Some(ref wheel) = *cell.borrow() {
+            return Rc::clone(wheel)
+        }
+        let wheel = Rc::new(RefCell::new(Wheel::new(clock::now())));
+        Arbiter::handle().spawn(Driver{wheel: Rc::downgrade(&wheel), delay: None});
+        *cell.borrow_mut() = Some(Rc::clone(&wheel));
+        wheel
+    })
+}
+
+struct Entry {
+    deadline: u64,
+    fired: bool,
+    task: Option<Task>,
+    /// slot and index within slot
+    pos: Option<(usize, usize)>,
+}
+
+struct Wheel {
+    start: Instant,
+    elapsed: u64,
+    entries: Vec<Entry>,
+    free: Vec<usize>,
+    slots: Vec<Vec<usize>>,
+    occupied: [u64; LEVELS],
+    driver: Option<Task>,
+    next: Option<u64>,
+    /// fired entries, tasks are notified once batch is complete
+    expired: Vec<usize>,
+}
+
+impl Wheel {
+    fn new(start: Instant) -> Wheel {
+        Wheel {
+            start: start,
+            elapsed: 0,
+            entries: Vec::new(),
+            free: Vec::new(),
+            slots: (0..OVERFLOW + 1).map(|_| Vec::new()).collect(),
+            occupied: [0; LEVELS],
+            driver: None,
+            next: None,
+            expired: Vec::new(),
+        }
+    }
+
+    // Deadline tick, rounded up so timers never fire early
+    fn tick_ceil(&self, at: Instant) -> u64 {
+        if at <= self.start {
+            return 0
+        }
+        let dur = at - self.start;
+        dur.as_secs() * 1000 + (u64::from(dur.subsec_nanos()) + 999_999) / 1_000_000
+    }
+
+    fn tick_floor(&self, at: Instant) -> u64 {
+        if at <= self.start {
+            return 0
+        }
+        let dur = at - self.start;
+        dur.as_secs() * 1000 + u64::from(dur.subsec_nanos()) / 1_000_000
+    }
+
+    fn instant(&self, tick: u64) -> Instant {
+        self.start + Duration::from_millis(tick)
+    }
+
+    fn add(&mut self, at: Instant) -> usize {
+        let entry = Entry{deadline: self.tick_ceil(at), fired: false, task: None, pos: None};
+        let key = match self.free.pop() {
+            Some(key) => {
+                self.entries[key] = entry;
+                key
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+        self.schedule(key);
+        key
+    }
+
+    fn reset(&mut self, key: usize, at: Instant) {
+        self.unlink(key);
+        self.entries[key].deadline = self.tick_ceil(at);
+        self.entries[key].fired = false;
+        self.schedule(key);
+    }
+
+    fn remove(&mut self, key: usize) {
+        self.unlink(key);
+        self.entries[key].task = None;
+        self.free.push(key);
+    }
+
+    fn poll(&mut self, key: usize) -> Async<()> {
+        let entry = &mut self.entries[key];
+        if entry.fired {
+            Async::Ready(())
+        } else {
+            if !entry.task.as_ref().map(|t| t.will_notify_current()).unwrap_or(false) {
+                entry.task = Some(task::current());
+            }
+            Async::NotReady
+        }
+    }
+
+    // Insert entry and wake up driver if entry expires before driver's deadline
+    fn schedule(&mut self, key: usize) {
+        self.insert(key);
+        self.notify_expired();
+        let deadline = self.entries[key].deadline;
+        if !self.entries[key].fired && self.next.map(|next| deadline < next).unwrap_or(true) {
+            if let Some(driver) = self.driver.take() {
+                driver.notify();
+            }
+        }
+    }
+
+    // Insert entry into its slot, expired entry is fired
+    fn insert(&mut self, key: usize) {
+        let deadline = self.entries[key].deadline;
+        if deadline <= self.elapsed {
+            self.entries[key].fired = true;
+            self.expired.push(key);
+            return
+        }
+
+        // level is the highest group of bits that differs from current time
+        let masked = self.elapsed ^ deadline;
+        let level = (63 - masked.leading_zeros() as usize) / BITS;
+        let slot = if level < LEVELS {
+            let slot = ((deadline >> (level * BITS)) as usize) & (SLOTS - 1);
+            self.occupied[level] |= 1 << slot;
+            level * SLOTS + slot
+        } else {
+            OVERFLOW
+        };
+        self.slots[slot].push(key);
+        self.entries[key].pos = Some((slot, self.slots[slot].len() - 1));
+    }
+
+    // Notify tasks of fired entries in deadline order
+    fn notify_expired(&mut self) {
+        if self.expired.is_empty() {
+            return
+        }
+        let mut expired = mem::replace(&mut self.expired, Vec::new());
+        {
+            let entries = &self.entries;
+            expired.sort_by_key(|key| entries[*key].deadline);
+        }
+        for key in expired.drain(..) {
+            if let Some(task) = self.entries[key].task.take() {
+                task.notify();
+            }
+        }
+        self.expired = expired;
+    }
+
+    fn unlink(&mut self, key: usize) {
+        if let Some((slot, idx)) = self.entries[key].pos.take() {
+            self.slots[slot].swap_remove(idx);
+            if idx < self.slots[slot].len() {
+                let moved = self.slots[slot][idx];
+                self.entries[moved].pos = Some((slot, idx));
+            }
+            if slot != OVERFLOW && self.slots[slot].is_empty() {
+                self.occupied[slot / SLOTS] &= !(1 << (slot % SLOTS));
+            }
+        }
+    }
+
+    // Earliest occupied slot, lower levels always expire before higher levels
+    fn next_slot(&self) -> Option<(usize, u64)> {
+        for level in 0..LEVELS {
+            let occupied = self.occupied[level];
+            if occupied == 0 {
+                continue
+            }
+            let shift = level * BITS;
+            let current = ((self.elapsed >> shift) as usize) & (SLOTS - 1);
+            let slot = (occupied.rotate_right(current as u32).trailing_zeros() as usize
+                        + current) % SLOTS;
+
+            let level_range = 1u64 << (shift + BITS);
+            let mut deadline = (self.elapsed & !(level_range - 1)) + ((slot as u64) << shift);
+            if slot < current {
+                deadline += level_range;
+            }
+            return Some((level * SLOTS + slot, deadline))
+        }
+        None
+    }
+
+    // Tick driver has to wake up at
+    fn next_expiration(&self) -> Option<u64> {
+        let next = self.next_slot().map(|(_, deadline)| deadline);
+        if self.slots[OVERFLOW].is_empty() {
+            next
+        } else {
+            let overflow = self.elapsed + (1 << (LEVELS * BITS));
+            Some(next.map(|next| cmp::min(next, overflow)).unwrap_or(overflow))
+        }
+    }
+
+    // Fire all timers expired by `now`
+    fn process(&mut self, now: u64) {
+        while let Some((slot, deadline)) = self.next_slot() {
+            if deadline > now {
+                break
+            }
+            if deadline > self.elapsed {
+                self.elapsed = deadline;
+            }
+            let keys = mem::replace(&mut self.slots[slot], Vec::new());
+            self.occupied[slot / SLOTS] &= !(1 << (slot % SLOTS));
+
+            // fire expired timers, cascade rest to lower levels
+            for key in keys {
+                self.entries[key].pos = None;
+                self.insert(key);
+            }
+        }
+        if now > self.elapsed {
+            self.elapsed = now;
+        }
+
+        if !self.slots[OVERFLOW].is_empty() {
+            let keys = mem::replace(&mut self.slots[OVERFLOW], Vec::new());
+            for key in keys {
+                self.entries[key].pos = None;
+                self.insert(key);
+            }
+        }
+        self.notify_expired();
+    }
+}
+
+/// Timer registered in arbiter's timer wheel
+pub(crate) struct Timer {
+    wheel: Rc<RefCell<Wheel>>,
+    key: usize,
+}
+
+impl Timer {
+    /// Create timer which fires after `dur`
+    pub fn new(dur: Duration) -> Timer {
+        Timer::new_at(clock::now() + dur)
+    }
+
+    /// Create timer which fires at `at`
+    pub fn new_at(at: Instant) -> Timer {
+        let wheel = current();
+        let key = wheel.borrow_mut().add(at);
+        Timer{wheel: wheel, key: key}
+    }
+
+    /// Reschedule timer to fire at `at`
+    pub fn reset(&mut self, at: Instant) {
+        self.wheel.borrow_mut().reset(self.key, at);
+    }
+}
+
+impl Future for Timer {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        Ok(self.wheel.borrow_mut().poll(self.key))
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.wheel.borrow_mut().remove(self.key);
+    }
+}
+
+/// Future that advances timer wheel
+struct Driver {
+    wheel: Weak<RefCell<Wheel>>,
+    delay: Option<(u64, Delay)>,
+}
+
+impl Future for Driver {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        loop {
+            let rc = match self.wheel.upgrade() {
+                Some(rc) => rc,
+                None => return Ok(Async::Ready(())),
+            };
+            let mut wheel = rc.borrow_mut();
+            let now = wheel.tick_floor(clock::now());
+            wheel.process(now);
+            wheel.driver = Some(task::current());
+            wheel.next = wheel.next_expiration();
+
+            let next = match wheel.next {
+                Some(next) => next,
+                None => {
+                    self.delay = None;
+                    return Ok(Async::NotReady)
+                }
+            };
+            if self.delay.as_ref().map(|&(tick, _)| tick != next).unwrap_or(true) {
+                self.delay = Some((next, Delay::new_at(wheel.instant(next))));
+            }
+            drop(wheel);
+
+            match self.delay.as_mut().unwrap().1.poll() {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                // fired delay is never polled again
+                Ok(Async::Ready(_)) | Err(_) => self.delay = None,
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Tracing of message flow
+//!
+//! Envelopes of sync and unsync addresses carry optional `TraceContext`.
+//! Message sent with `send()` or `do_send()` from within a handler of
+//! traced message gets child span of handler's context, so request could
+//! be followed across actors and arbiters. Trace is started with `scope()`,
+//! outside of actors or for any message.
+//!
+//! Current context is available with `ctx.trace_context()` during message
+//! handling. Its `Display` implementation renders context as
+//! `trace_id=.. span_id=.. parent_id=..` key-values, so it could be
+//! attached to log records. Actix logs each traced message at `trace`
+//! level with `actix::trace` target.
+//!
+//! Context is not propagated to futures returned from handlers or spawned
+//! into actor's context, use `scope()` to send messages from such futures.
+//!
+//! ```rust
+//! # extern crate actix;
+//! use actix::prelude::*;
+//! use actix::trace::{self, TraceContext};
+//!
+//! struct Ping;
+//!
+//! impl Message for Ping {
+//!     type Result = ();
+//! }
+//!
+//! struct MyActor;
+//!
+//! impl Actor for MyActor {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<Ping> for MyActor {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, _: Ping, ctx: &mut Context<Self>) {
+//!         let trace = ctx.trace_context().unwrap();
+//!         println!("{} handle ping", trace);
+//! #       Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let sys = System::new("example");
+//!     let addr: Addr<Syn, _> = MyActor.start();
+//!
+//!     trace::scope(TraceContext::new(), || addr.do_send(Ping));
+//!     sys.run();
+//! }
+//! ```
+use std::fmt;
+use std::cell::Cell;
+
+use uuid::Uuid;
+
+
+thread_local!(
+    static CURRENT: Cell<Option<TraceContext>> = Cell::new(None);
+);
+
+/// Trace context of a message
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    trace_id: u64,
+    span_id: u64,
+    parent_id: Option<u64>,
+}
+
+impl TraceContext {
+    /// Start new trace
+    pub fn new() -> TraceContext {
+        TraceContext{trace_id: random_id(), span_id: random_id(), parent_id: None}
+    }
+
+    /// Context with specific ids, i.e. continue trace of incoming request
+    pub fn from_ids(trace_id: u64, span_id: u64, parent_id: Option<u64>) -> TraceContext {
+        TraceContext{trace_id: trace_id, span_id: span_id, parent_id: parent_id}
+    }
+
+    /// Create child span of this context
+    pub fn child(&self) -> TraceContext {
+        TraceContext{trace_id: self.trace_id, span_id: random_id(), parent_id: Some(self.span_id)}
+    }
+
+    /// Id of the trace, same for all spans of the trace
+    pub fn trace_id(&self) -> u64 {
+        self.trace_id
+    }
+
+    /// Id of the span
+    pub fn span_id(&self) -> u64 {
+        self.span_id
+    }
+
+    /// Id of the parent span, `None` for root span
+    pub fn parent_id(&self) -> Option<u64> {
+        self.parent_id
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> TraceContext {
+        TraceContext::new()
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "trace_id={:016x} span_id={:016x}", self.trace_id, self.span_id)?;
+        if let Some(parent_id) = self.parent_id {
+            write!(f, " parent_id={:016x}", parent_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Trace context of the handler currently executing in this thread
+pub fn current() -> Option<TraceContext> {
+    CURRENT.with(|cell| cell.get())
+}
+
+/// Run `f` with `trace` as current context, messages sent from `f`
+/// become child spans of `trace`
+pub fn scope<F, R>(trace: TraceContext, f: F) -> R where F: FnOnce() -> R {
+    let _guard = enter(Some(trace));
+    f()
+}
+
+/// Context for envelope of new message
+pub(crate) fn child() -> Option<TraceContext> {
+    current().map(|trace| trace.child())
+}
+
+/// Set current context, previous one is restored on guard drop,
+/// including unwinding from panicked handler
+pub(crate) fn enter(trace: Option<TraceContext>) -> Guard {
+    Guard(CURRENT.with(|cell| cell.replace(trace)))
+}
+
+pub(crate) struct Guard(Option<TraceContext>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let prev = self.0;
+        CURRENT.with(|cell| cell.set(prev));
+    }
+}
+
+fn random_id() -> u64 {
+    let bytes = Uuid::new_v4();
+    let id = bytes.as_bytes()[..8].iter().fold(0u64, |id, b| (id << 8) | u64::from(*b));
+    if id == 0 { 1 } else { id }
+}
@@ -1,11 +1,11 @@
-use std::time::Duration;
-use futures::{Async, Future, Poll};
+use std::time::{Duration, Instant};
+use futures::{task, Async, Future, Poll};
 use futures::unsync::oneshot;
-use tokio_core::reactor::Timeout;
 
 use fut::ActorFuture;
-use actor::Actor;
-use arbiter::Arbiter;
+use actor::{Actor, AsyncContext, MissedTick};
+use clock;
+use timer::Timer;
 
 pub struct Condition<T> where T: Clone {
     waiters: Vec<oneshot::Sender<T>>,
@@ -35,7 +35,7 @@ impl<T> Default for Condition<T> where T: Clone {
 pub(crate)
 struct TimerFunc<A> where A: Actor {
     f: Option<Box<TimerFuncBox<A>>>,
-    timeout: Timeout,
+    timeout: Timer,
 }
 
 impl<A> TimerFunc<A> where A: Actor {
@@ -44,7 +44,7 @@ impl<A> TimerFunc<A> where A: Actor {
     {
         TimerFunc {
             f: Some(Box::new(f)),
-            timeout: Timeout::new(timeout, Arbiter::handle()).unwrap()}
+            timeout: Timer::new(timeout)}
     }
 }
 
@@ -81,3 +81,117 @@ impl<A> ActorFuture for TimerFunc<A> where A: Actor {
         }
     }
 }
+
+/// Schedule of periodic ticks
+pub(crate) struct Ticker {
+    interval: Duration,
+    policy: MissedTick,
+    deadline: Instant,
+    timeout: Timer,
+}
+
+impl Ticker {
+    pub fn new(interval: Duration, policy: MissedTick) -> Ticker {
+        let deadline = clock::now() + interval;
+        Ticker {
+            interval: interval,
+            policy: policy,
+            deadline: deadline,
+            timeout: Timer::new_at(deadline)}
+    }
+
+    /// Check if tick is due
+    pub fn poll(&mut self) -> Async<()> {
+        match self.timeout.poll() {
+            Ok(Async::Ready(_)) => Async::Ready(()),
+            Ok(Async::NotReady) => Async::NotReady,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Schedule next tick, returns false if next tick is due already
+    pub fn next(&mut self) -> bool {
+        let now = clock::now();
+        self.deadline = match self.policy {
+            MissedTick::Burst => self.deadline + self.interval,
+            MissedTick::Skip => {
+                let mut next = self.deadline + self.interval;
+                if self.interval > Duration::new(0, 0) {
+                    while next <= now {
+                        next += self.interval;
+                    }
+                }
+                next
+            }
+            MissedTick::Delay => now + self.interval,
+        };
+        self.timeout.reset(self.deadline);
+        self.deadline > now
+    }
+}
+
+pub(crate)
+struct IntervalFunc<A> where A: Actor {
+    f: Box<FnMut(&mut A, &mut A::Context)>,
+    ticker: Ticker,
+}
+
+impl<A> IntervalFunc<A> where A: Actor {
+    pub fn new<F>(interval: Duration, policy: MissedTick, f: F) -> IntervalFunc<A>
+        where F: FnMut(&mut A, &mut A::Context) + 'static
+    {
+        IntervalFunc {
+            f: Box::new(f),
+            ticker: Ticker::new(interval, policy)}
+    }
+}
+
+#[doc(hidden)]
+impl<A> ActorFuture for IntervalFunc<A> where A: Actor, A::Context: AsyncContext<A> {
+    type Item = ();
+    type Error = ();
+    type Actor = A;
+
+    fn poll(&mut self, act: &mut Self::Actor, ctx: &mut <Self::Actor as Actor>::Context)
+            -> Poll<Self::Item, Self::Error>
+    {
+        loop {
+            if let Async::NotReady = self.ticker.poll() {
+                return Ok(Async::NotReady)
+            }
+            (self.f)(act, ctx);
+
+            // give other context items a chance to run between missed ticks
+            if !self.ticker.next() || ctx.waiting() {
+                task::current().notify();
+                return Ok(Async::NotReady)
+            }
+        }
+    }
+}
+
+/// Name of type `T` used in logs, dead letters, metrics and introspection
+#[cfg(actix_type_name)]
+pub(crate) fn type_name<T: ?Sized + 'static>() -> &'static str {
+    ::std::any::type_name::<T>()
+}
+
+/// Compilers older than 1.38 can not name types, type id is used as name
+#[cfg(not(actix_type_name))]
+pub(crate) fn type_name<T: ?Sized + 'static>() -> &'static str {
+    use std::any::TypeId;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    thread_local!(
+        static NAMES: RefCell<HashMap<TypeId, &'static str>> = RefCell::new(HashMap::new());
+    );
+
+    NAMES.with(|names| {
+        *names.borrow_mut().entry(TypeId::of::<T>()).or_insert_with(|| {
+            // name is leaked, once per type and thread
+            let name = format!("{:?}", TypeId::of::<T>()).into_boxed_str();
+            unsafe { &*Box::into_raw(name) }
+        })
+    })
+}
@@ -0,0 +1,334 @@
+//! Slow handler and stuck arbiter watchdog
+//!
+//! Blocking message handler starves all actors of its arbiter. Watchdog
+//! times each message handler and each poll of a future spawned into
+//! actor's context and logs a warning with actor and message type if it
+//! takes longer than the threshold. Monitor thread watches event loops of
+//! arbiters and logs an error with the actor and message currently being
+//! handled if arbiter's event loop has not ticked within the deadline.
+//!
+//! Watchdog is disabled by default. `Watchdog::start()` enables it for
+//! arbiter it is called in and all arbiters started later, so it has to
+//! be started right after system creation. Events could be delivered to
+//! a recipient in addition to the log, see `Watchdog::notify()`.
+//!
+//! ```rust
+//! # extern crate actix;
+//! use std::time::Duration;
+//! use actix::prelude::*;
+//! use actix::watchdog::Watchdog;
+//!
+//! fn main() {
+//!     let sys = System::new("example");
+//!
+//!     Watchdog::new()
+//!         .threshold(Duration::from_millis(50))
+//!         .deadline(Duration::from_secs(1))
+//!         .start();
+//!
+//! #   Arbiter::system().do_send(actix::msgs::SystemExit(0));
+//!     sys.run();
+//! }
+//! ```
+use std::thread;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::{Future, Stream};
+use tokio_core::reactor::Interval;
+
+use address::{Recipient, Syn};
+use arbiter::Arbiter;
+use handler::Message;
+use utils::type_name;
+
+
+/// Number of event loop ticks and monitor checks within deadline
+const CHECKS_PER_DEADLINE: u32 = 4;
+
+/// Set once any watchdog is started, handlers skip watchdog before that
+static STARTED: AtomicBool = ATOMIC_BOOL_INIT;
+
+thread_local!(
+    static CURRENT: RefCell<Option<Watch>> = RefCell::new(None);
+);
+
+/// Watchdog configuration
+#[derive(Clone)]
+pub struct Watchdog {
+    threshold: Duration,
+    deadline: Duration,
+    notify: Option<Recipient<Syn, WatchdogEvent>>,
+}
+
+/// Event reported by watchdog
+#[derive(Clone, Debug)]
+pub enum WatchdogEvent {
+    /// Message handler or future poll took longer than threshold
+    Slow {
+        /// Name of the arbiter
+        arbiter: String,
+        /// Type name of the actor
+        actor: &'static str,
+        /// Type name of the message, `None` for future spawned into context
+        message: Option<&'static str>,
+        /// Time handler took
+        elapsed: Duration,
+    },
+    /// Arbiter's event loop has not ticked within deadline
+    Stuck {
+        /// Name of the arbiter
+        arbiter: String,
+        /// Time since last tick of event loop
+        idle: Duration,
+        /// Type name of the actor running at the moment, if any
+        actor: Option<&'static str>,
+        /// Type name of the message being handled at the moment, if any
+        message: Option<&'static str>,
+    },
+}
+
+impl Message for WatchdogEvent {
+    type Result = ();
+}
+
+impl Default for Watchdog {
+    fn default() -> Watchdog {
+        Watchdog::new()
+    }
+}
+
+impl Watchdog {
+    /// Watchdog with 100 milliseconds threshold and 5 seconds deadline
+    pub fn new() -> Watchdog {
+        Watchdog {
+            threshold: Duration::from_millis(100),
+            deadline: Duration::from_secs(5),
+            notify: None,
+        }
+    }
+
+    /// Warn about handlers that run longer than `threshold`
+    pub fn threshold(mut self, threshold: Duration) -> Watchdog {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Report arbiters whose event loop has not ticked within `deadline`
+    pub fn deadline(mut self, deadline: Duration) -> Watchdog {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Send events to `rcp` in addition to the log
+    pub fn notify(mut self, rcp: Recipient<Syn, WatchdogEvent>) -> Watchdog {
+        self.notify = Some(rcp);
+        self
+    }
+
+    /// Enable watchdog for current system and start monitor thread
+    ///
+    /// Current arbiter and arbiters started afterwards are watched.
+    /// Watchdog could be started only once per system.
+    pub fn start(self) {
+        let state = Arbiter::watchdog();
+        {
+            let mut inner = state.0.lock().unwrap();
+            if inner.config.is_some() {
+                warn!("Watchdog is started already");
+                return
+            }
+            inner.config = Some(self.clone());
+        }
+        STARTED.store(true, Ordering::Relaxed);
+        state.arbiter_started(&Arbiter::name());
+
+        let state = Arc::downgrade(&state.0);
+        let _ = thread::Builder::new().name("actix-watchdog".to_owned()).spawn(move || {
+            let period = self.deadline / CHECKS_PER_DEADLINE;
+            loop {
+                thread::sleep(period);
+                match state.upgrade() {
+                    Some(state) => check(&state, &self),
+                    None => return,
+                }
+            }
+        });
+    }
+
+    fn report(&self, event: WatchdogEvent) {
+        if let Some(ref rcp) = self.notify {
+            let _ = rcp.do_send(event);
+        }
+    }
+}
+
+/// Watchdog of a system, shared by all arbiters of the system
+#[derive(Clone)]
+pub(crate) struct WatchdogState(Arc<Mutex<WatchdogInner>>);
+
+struct WatchdogInner {
+    config: Option<Watchdog>,
+    arbiters: Vec<Weak<Beat>>,
+}
+
+impl WatchdogState {
+    pub fn new() -> WatchdogState {
+        WatchdogState(Arc::new(Mutex::new(WatchdogInner{config: None, arbiters: Vec::new()})))
+    }
+
+    /// Watch current arbiter if watchdog is enabled
+    pub fn arbiter_started(&self, name: &str) {
+        let (beat, config) = {
+            let mut inner = self.0.lock().unwrap();
+            let config = match inner.config {
+                Some(ref config) => config.clone(),
+                None => return,
+            };
+            let beat = Arc::new(Beat {
+                arbiter: name.to_owned(),
+                state: Mutex::new(BeatState{tick: Instant::now(), current: None, stuck: false}),
+            });
+            inner.arbiters.retain(|beat| beat.upgrade().is_some());
+            inner.arbiters.push(Arc::downgrade(&beat));
+            (beat, config)
+        };
+        let period = config.deadline / CHECKS_PER_DEADLINE;
+        CURRENT.with(|cell| {
+            *cell.borrow_mut() = Some(Watch{beat: Arc::clone(&beat), config: config})
+        });
+
+        // event loop ticks, timer does not fire while loop is blocked
+        match Interval::new(period, Arbiter::handle()) {
+            Ok(interval) => Arbiter::handle().spawn(
+                interval
+                    .for_each(move |_| {
+                        beat.state.lock().unwrap().tick = Instant::now();
+                        Ok(())
+                    })
+                    .map_err(|err| error!("Watchdog timer error: {}", err))),
+            Err(err) => error!("Can not start watchdog timer: {}", err),
+        }
+    }
+}
+
+/// Stop watching current thread, i.e. on new event loop
+pub(crate) fn reset() {
+    CURRENT.with(|cell| *cell.borrow_mut() = None);
+}
+
+struct Watch {
+    beat: Arc<Beat>,
+    config: Watchdog,
+}
+
+/// Event loop state of an arbiter
+struct Beat {
+    arbiter: String,
+    state: Mutex<BeatState>,
+}
+
+struct BeatState {
+    tick: Instant,
+    // actor, message and start time of running handler
+    current: Option<(&'static str, Option<&'static str>, Instant)>,
+    stuck: bool,
+}
+
+/// Mark start of message handling or future poll of actor `A`, end is
+/// reported on guard drop. Noop if current arbiter is not watched.
+pub(crate) fn enter<A: 'static>(message: Option<&'static str>) -> Guard {
+    if !STARTED.load(Ordering::Relaxed) {
+        return Guard(false)
+    }
+    Guard(CURRENT.with(|cell| match *cell.borrow() {
+        Some(ref watch) => {
+            let current = Some((type_name::<A>(), message, Instant::now()));
+            watch.beat.state.lock().unwrap().current = current;
+            true
+        }
+        None => false,
+    }))
+}
+
+pub(crate) struct Guard(bool);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if self.0 {
+            CURRENT.with(|cell| if let Some(ref watch) = *cell.borrow() {
+                watch.finished()
+            })
+        }
+    }
+}
+
+impl Watch {
+    /// Running handler is finished, report it if it was slow
+    fn finished(&self) {
+        let beat = &self.beat;
+        let current = beat.state.lock().unwrap().current.take();
+        if let Some((actor, message, started)) = current {
+            let elapsed = started.elapsed();
+            if elapsed > self.config.threshold {
+                match message {
+                    Some(message) => warn!(
+                        "Slow handler in arbiter {}: actor {} handled {} in {:?}",
+                        beat.arbiter, actor, message, elapsed),
+                    None => warn!(
+                        "Slow future in arbiter {}: actor {} polled future in {:?}",
+                        beat.arbiter, actor, elapsed),
+                }
+                self.config.report(WatchdogEvent::Slow {
+                    arbiter: beat.arbiter.clone(),
+                    actor: actor,
+                    message: message,
+                    elapsed: elapsed,
+                });
+            }
+        }
+    }
+}
+
+/// Report arbiters that did not tick within deadline, once per stall
+fn check(state: &Mutex<WatchdogInner>, config: &Watchdog) {
+    let arbiters: Vec<_> = {
+        let mut inner = state.lock().unwrap();
+        inner.arbiters.retain(|beat| beat.upgrade().is_some());
+        inner.arbiters.iter().filter_map(|beat| beat.upgrade()).collect()
+    };
+
+    for beat in arbiters {
+        let mut beat_state = beat.state.lock().unwrap();
+        let idle = beat_state.tick.elapsed();
+        if idle <= config.deadline {
+            beat_state.stuck = false;
+            continue
+        }
+        if beat_state.stuck {
+            continue
+        }
+        beat_state.stuck = true;
+
+        let (actor, message) = match beat_state.current {
+            Some((actor, message, started)) => {
+                error!("Arbiter {} is stuck for {:?}: actor {} is running {} for {:?}",
+                       beat.arbiter, idle, actor, message.unwrap_or("future"),
+                       started.elapsed());
+                (Some(actor), message)
+            }
+            None => {
+                error!("Arbiter {} is stuck for {:?}", beat.arbiter, idle);
+                (None, None)
+            }
+        };
+        config.report(WatchdogEvent::Stuck {
+            arbiter: beat.arbiter.clone(),
+            idle: idle,
+            actor: actor,
+            message: message,
+        });
+    }
+}
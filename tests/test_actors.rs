@@ -1,8 +1,18 @@
-extern crate actix;
+#[macro_use] extern crate actix;
 extern crate futures;
+extern crate tokio_core;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use futures::Future;
+use tokio_core::reactor::Timeout;
 use actix::prelude::*;
-use actix::actors::{self, signal};
+use actix::actors::{self, signal, dead_letters};
+use actix::actors::dead_letters::DeadLetters;
+use actix::actors::broker::{Broker, Subscribe, Publish, IssueSync};
+use actix::actors::event_bus::{self, Event as BusEvent, EventBus, SubscriberPolicy};
+use actix::msgs::{DeadLetter, DeadLetterReason};
 
 
 #[test]
@@ -72,3 +82,307 @@ fn test_signal_int() {
     });
     sys.run();
 }
+
+struct DeadLetterCollector(Arc<Mutex<Vec<DeadLetter>>>);
+
+impl Actor for DeadLetterCollector {
+    type Context = Context<Self>;
+}
+
+impl Handler<DeadLetter> for DeadLetterCollector {
+    type Result = ();
+
+    fn handle(&mut self, msg: DeadLetter, _: &mut Self::Context) {
+        self.0.lock().unwrap().push(msg);
+    }
+}
+
+#[derive(Message)]
+struct Ping;
+
+struct StoppedActor;
+
+impl Actor for StoppedActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+impl Handler<Ping> for StoppedActor {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+impl Handler<signal::Signal> for StoppedActor {
+    type Result = ();
+
+    fn handle(&mut self, _: signal::Signal, _: &mut Self::Context) {}
+}
+
+impl Handler<Event> for StoppedActor {
+    type Result = usize;
+
+    fn handle(&mut self, _: Event, _: &mut Self::Context) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_dead_letters() {
+    let sys = System::new("test");
+    let letters = Arc::new(Mutex::new(Vec::new()));
+
+    let collector: Addr<Syn, _> = DeadLetterCollector(Arc::clone(&letters)).start();
+    let office = DeadLetters::from_registry();
+    office.do_send(dead_letters::Subscribe(collector.recipient()));
+
+    let addr: Addr<Syn, _> = StoppedActor.start();
+
+    Arbiter::handle().spawn(
+        Timeout::new(Duration::new(0, 10_000_000), Arbiter::handle()).unwrap()
+            .then(move |_| {
+                assert!(addr.try_send(Ping).is_err());
+                addr.do_send(Ping);
+                Timeout::new(Duration::new(0, 10_000_000), Arbiter::handle()).unwrap()
+            })
+            .then(|_| {
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                Ok::<_, ()>(())
+            })
+    );
+    sys.run();
+
+    let letters = letters.lock().unwrap();
+    assert_eq!(letters.len(), 2);
+    for letter in letters.iter() {
+        assert_eq!(letter.reason, DeadLetterReason::Closed);
+        assert!(letter.actor.ends_with("StoppedActor"));
+        assert!(letter.message.ends_with("Ping"));
+    }
+}
+
+struct WaitingActor;
+
+impl Actor for WaitingActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // mailbox is never processed
+        ctx.wait(actix::fut::wrap_future(futures::future::empty()));
+    }
+}
+
+impl Handler<Ping> for WaitingActor {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+#[test]
+fn test_dead_letters_recipient_timeout() {
+    let sys = System::new("test");
+    let letters = Arc::new(Mutex::new(Vec::new()));
+
+    let collector: Addr<Syn, _> = DeadLetterCollector(Arc::clone(&letters)).start();
+    let office = DeadLetters::from_registry();
+    office.do_send(dead_letters::Subscribe(collector.recipient()));
+
+    let addr: Addr<Syn, _> = WaitingActor.start();
+    let recipient = addr.recipient::<Ping>();
+
+    Arbiter::handle().spawn(
+        recipient.send(Ping).timeout(Duration::new(0, 10_000_000))
+            .then(|res| {
+                match res {
+                    Err(MailboxError::Timeout) => (),
+                    _ => panic!("Should time out"),
+                }
+                Timeout::new(Duration::new(0, 10_000_000), Arbiter::handle()).unwrap()
+            })
+            .then(|_| {
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                Ok::<_, ()>(())
+            })
+    );
+    sys.run();
+
+    let letters = letters.lock().unwrap();
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].reason, DeadLetterReason::Timeout);
+    assert!(letters[0].actor.ends_with("WaitingActor"));
+    assert!(letters[0].message.ends_with("Ping"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_signal_dead_subscriber() {
+    let sys = System::new("test");
+    let letters = Arc::new(Mutex::new(Vec::new()));
+
+    let collector: Addr<Syn, _> = DeadLetterCollector(Arc::clone(&letters)).start();
+    DeadLetters::from_registry().do_send(dead_letters::Subscribe(collector.recipient()));
+
+    let addr: Addr<Syn, _> = StoppedActor.start();
+    let sig = Arbiter::system_registry().get::<signal::ProcessSignals>();
+    sig.do_send(signal::Subscribe(addr.recipient()));
+
+    Arbiter::handle().spawn(
+        Timeout::new(Duration::new(0, 10_000_000), Arbiter::handle()).unwrap()
+            .then(move |_| {
+                // subscriber is removed after first failed delivery
+                sig.do_send(signal::SignalType::Hup);
+                sig.do_send(signal::SignalType::Hup);
+                Timeout::new(Duration::new(0, 10_000_000), Arbiter::handle()).unwrap()
+            })
+            .then(|_| {
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                Ok::<_, ()>(())
+            })
+    );
+    sys.run();
+
+    let letters = letters.lock().unwrap();
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].reason, DeadLetterReason::Closed);
+    assert!(letters[0].actor.ends_with("StoppedActor"));
+    assert!(letters[0].message.ends_with("signal::Signal"));
+}
+
+#[derive(Clone)]
+struct Event(usize);
+
+impl Message for Event {
+    type Result = usize;
+}
+
+struct Listener(Arc<AtomicUsize>);
+
+impl Actor for Listener {
+    type Context = Context<Self>;
+}
+
+impl Handler<Event> for Listener {
+    type Result = usize;
+
+    fn handle(&mut self, msg: Event, _: &mut Self::Context) -> usize {
+        self.0.fetch_add(msg.0, Ordering::Relaxed);
+        msg.0
+    }
+}
+
+#[test]
+fn test_broker() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let l1: Addr<Syn, _> = Listener(Arc::clone(&count)).start();
+    let l2: Addr<Syn, _> = Listener(Arc::clone(&count)).start();
+    let dead: Addr<Syn, _> = StoppedActor.start();
+
+    let broker = Arbiter::system_registry().get::<Broker>();
+    let id = broker.send(Subscribe(l1.recipient()));
+    broker.do_send(Subscribe(l2.recipient()));
+    broker.do_send(Subscribe(dead.recipient::<Event>()));
+
+    Arbiter::handle().spawn(id.then(move |id| {
+        let id = id.unwrap();
+        broker.do_send(Publish(Event(1)));
+        broker.send(IssueSync(Event(10), id)).then(|res| {
+            assert_eq!(res.unwrap(), Ok(vec![10]));
+            Arbiter::system().do_send(actix::msgs::SystemExit(0));
+            Ok::<_, ()>(())
+        })
+    }));
+    sys.run();
+
+    assert_eq!(count.load(Ordering::Relaxed), 12);
+}
+
+struct TopicCollector {
+    capacity: usize,
+    topics: Arc<Mutex<Vec<String>>>,
+}
+
+impl Actor for TopicCollector {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.set_mailbox_capacity(self.capacity);
+    }
+}
+
+impl Handler<BusEvent> for TopicCollector {
+    type Result = ();
+
+    fn handle(&mut self, ev: BusEvent, _: &mut Self::Context) {
+        self.topics.lock().unwrap().push(ev.topic().to_owned());
+    }
+}
+
+fn run_event_bus<F>(capacity: usize, f: F) -> (Vec<String>, Vec<DeadLetter>)
+    where F: FnOnce(Recipient<Syn, BusEvent>) -> event_bus::Subscribe
+{
+    let sys = System::new("test");
+    let topics = Arc::new(Mutex::new(Vec::new()));
+    let letters = Arc::new(Mutex::new(Vec::new()));
+
+    let collector: Addr<Syn, _> = DeadLetterCollector(Arc::clone(&letters)).start();
+    DeadLetters::from_registry().do_send(dead_letters::Subscribe(collector.recipient()));
+
+    let addr: Addr<Syn, _> = TopicCollector{
+        capacity: capacity, topics: Arc::clone(&topics)}.start();
+    let bus = EventBus::from_registry();
+    bus.do_send(f(addr.recipient()));
+
+    Arbiter::handle().spawn(
+        Timeout::new(Duration::new(0, 10_000_000), Arbiter::handle()).unwrap()
+            .then(move |_| {
+                for topic in &["orders.1.created", "orders.2.deleted", "orders.3.created",
+                               "users.4.created", "orders.5.created", "orders.6.created"] {
+                    bus.do_send(event_bus::Publish(BusEvent::new(*topic, ())));
+                }
+                Timeout::new(Duration::new(0, 100_000_000), Arbiter::handle()).unwrap()
+            })
+            .then(|_| {
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                Ok::<_, ()>(())
+            })
+    );
+    sys.run();
+
+    let res = (topics.lock().unwrap().clone(), letters.lock().unwrap().clone());
+    res
+}
+
+#[test]
+fn test_event_bus_wildcard() {
+    let (topics, _) = run_event_bus(16, |rcp| event_bus::Subscribe::new("orders.*.created", rcp));
+    assert_eq!(topics, vec!["orders.1.created", "orders.3.created",
+                            "orders.5.created", "orders.6.created"]);
+}
+
+#[test]
+fn test_event_bus_multi_wildcard() {
+    let (topics, _) = run_event_bus(16, |rcp| event_bus::Subscribe::new("#.created", rcp));
+    assert_eq!(topics.len(), 5);
+}
+
+#[test]
+fn test_event_bus_buffer() {
+    // one event fits into mailbox, two get buffered
+    let (topics, letters) = run_event_bus(1, |rcp| {
+        event_bus::Subscribe::new("orders.#", rcp).buffer(2, SubscriberPolicy::DropNewest)
+    });
+    assert_eq!(topics, vec!["orders.1.created", "orders.2.deleted", "orders.3.created"]);
+
+    // rest of the events is dropped
+    let dropped: Vec<_> = letters.iter().map(|l| l.topic.clone().unwrap()).collect();
+    assert_eq!(dropped, vec!["orders.5.created", "orders.6.created"]);
+    for letter in &letters {
+        assert_eq!(letter.reason, DeadLetterReason::Dropped);
+        assert!(letter.actor.ends_with("TopicCollector"));
+    }
+}
@@ -322,3 +322,71 @@ fn test_sync_call_message_timeout() {
     sys.run();
     assert_eq!(count.load(Ordering::Relaxed), 1);
 }
+
+struct StopActor(Arc<AtomicUsize>);
+
+impl Actor for StopActor {
+    type Context = Context<Self>;
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Handler<Ping> for StopActor {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+#[test]
+fn test_weak_address() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let addr: Addr<Unsync, _> = StopActor(Arc::clone(&count)).start();
+    let weak = addr.downgrade();
+
+    Arbiter::handle().spawn_fn(move || {
+        let addr2 = weak.upgrade().unwrap();
+        addr2.do_send(Ping(0));
+        drop(addr);
+        drop(addr2);
+
+        Timeout::new(Duration::new(0, 1_000_000), Arbiter::handle()).unwrap()
+            .then(move |_| {
+                assert!(weak.upgrade().is_none());
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                future::result(Ok(()))
+            })
+    });
+
+    sys.run();
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_sync_weak_recipient() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let addr: Addr<Syn, _> = StopActor(Arc::clone(&count)).start();
+    let weak = addr.clone().recipient::<Ping>().downgrade();
+
+    Arbiter::handle().spawn_fn(move || {
+        let recipient = weak.upgrade().unwrap();
+        let _ = recipient.do_send(Ping(0));
+        drop(addr);
+        drop(recipient);
+
+        Timeout::new(Duration::new(0, 1_000_000), Arbiter::handle()).unwrap()
+            .then(move |_| {
+                assert!(weak.upgrade().is_none());
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                future::result(Ok(()))
+            })
+    });
+
+    sys.run();
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
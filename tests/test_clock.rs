@@ -0,0 +1,121 @@
+extern crate actix;
+extern crate futures;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use futures::{future, Future};
+use actix::prelude::*;
+use actix::fut;
+use actix::clock::TestClock;
+use actix::msgs::SystemExit;
+
+const HOUR: u64 = 3600;
+
+struct Later(TestClock, Arc<Mutex<Vec<&'static str>>>);
+
+impl Actor for Later {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::from_secs(2 * HOUR), |act, _| {
+            act.1.lock().unwrap().push("second");
+            Arbiter::system().do_send(SystemExit(0));
+        });
+        ctx.run_later(Duration::from_secs(HOUR), |act, _| {
+            act.1.lock().unwrap().push("first");
+            act.0.advance(Duration::from_secs(HOUR));
+        });
+        self.0.advance(Duration::from_secs(HOUR));
+    }
+}
+
+#[test]
+fn test_run_later() {
+    let sys = System::new("test");
+    let start = Instant::now();
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let _: () = Later(System::test_clock(), Arc::clone(&events)).start();
+    sys.run();
+
+    assert_eq!(*events.lock().unwrap(), vec!["first", "second"]);
+    assert!(start.elapsed() < Duration::from_secs(HOUR));
+}
+
+struct Ping;
+
+impl Message for Ping {
+    type Result = Result<(), ()>;
+}
+
+struct Silent;
+
+impl Actor for Silent {
+    type Context = Context<Self>;
+}
+
+impl Handler<Ping> for Silent {
+    type Result = Response<(), ()>;
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) -> Self::Result {
+        // never responds
+        Response::async(future::empty())
+    }
+}
+
+#[test]
+fn test_request_timeout() {
+    let sys = System::new("test");
+    let clock = System::test_clock();
+    let result = Arc::new(Mutex::new(None));
+    let result2 = Arc::clone(&result);
+
+    let addr: Addr<Syn, _> = Silent.start();
+    Arbiter::handle().spawn(
+        addr.send(Ping).timeout(Duration::from_secs(30)).then(move |res| {
+            *result2.lock().unwrap() = Some(res);
+            Arbiter::system().do_send(SystemExit(0));
+            Ok(())
+        }));
+    clock.advance(Duration::from_secs(30));
+    sys.run();
+
+    match result.lock().unwrap().take() {
+        Some(Err(MailboxError::Timeout)) => (),
+        _ => panic!("Request has to time out"),
+    }
+}
+
+struct Waiter(TestClock, Arc<Mutex<Vec<&'static str>>>);
+
+impl Actor for Waiter {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        fut::wrap_future::<_, Self>(future::empty::<(), &'static str>())
+            .timeout(Duration::from_secs(HOUR), "timeout")
+            .map_err(|err, act, _| {
+                act.1.lock().unwrap().push(err);
+                Arbiter::system().do_send(SystemExit(0));
+            })
+            .spawn(ctx);
+
+        // not yet
+        self.0.advance(Duration::from_secs(HOUR - 1));
+        ctx.run_later(Duration::new(0, 0), |act, _| {
+            act.1.lock().unwrap().push("tick");
+            act.0.advance(Duration::from_secs(1));
+        });
+    }
+}
+
+#[test]
+fn test_future_timeout() {
+    let sys = System::new("test");
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let _: () = Waiter(System::test_clock(), Arc::clone(&events)).start();
+    sys.run();
+
+    assert_eq!(*events.lock().unwrap(), vec!["tick", "timeout"]);
+}
@@ -0,0 +1,129 @@
+extern crate actix;
+
+use std::thread;
+use std::sync::{Arc, Mutex};
+use actix::prelude::*;
+use actix::msgs::SystemExit;
+
+const SENDERS: usize = 3;
+const MESSAGES: usize = 20;
+
+struct Event(usize, usize);
+
+impl Message for Event {
+    type Result = ();
+}
+
+struct Collector(Arc<Mutex<Vec<(usize, usize)>>>);
+
+impl Actor for Collector {
+    type Context = Context<Self>;
+}
+
+impl Handler<Event> for Collector {
+    type Result = ();
+
+    fn handle(&mut self, msg: Event, _: &mut Self::Context) {
+        let mut events = self.0.lock().unwrap();
+        events.push((msg.0, msg.1));
+        if events.len() == SENDERS * MESSAGES {
+            Arbiter::system().do_send(SystemExit(0));
+        }
+    }
+}
+
+struct Worker(Addr<Syn, Collector>);
+
+impl Actor for Worker {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<Event> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, msg: Event, _: &mut Self::Context) {
+        self.0.do_send(msg);
+    }
+}
+
+struct Sender(usize, Recipient<Syn, Event>);
+
+impl Actor for Sender {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        for i in 0..MESSAGES {
+            ctx.notify(Event(self.0, i));
+        }
+    }
+}
+
+impl Handler<Event> for Sender {
+    type Result = ();
+
+    fn handle(&mut self, msg: Event, _: &mut Self::Context) {
+        let _ = self.1.do_send(msg);
+    }
+}
+
+fn run(seed: u64) -> Vec<(usize, usize)> {
+    let sys = System::deterministic(seed);
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let collector: Addr<Syn, _> = Collector(Arc::clone(&events)).start();
+    let worker = {
+        let collector = collector.clone();
+        SyncArbiter::start(2, move || Worker(collector.clone()))
+    };
+    for idx in 0..SENDERS {
+        // last sender goes through sync workers
+        let recipient = if idx == SENDERS - 1 {
+            worker.clone().recipient()
+        } else {
+            collector.clone().recipient()
+        };
+        let _: Addr<Syn, _> = Arbiter::start(move |_| Sender(idx, recipient));
+    }
+    sys.run();
+
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+#[test]
+fn test_same_seed() {
+    let events = run(7);
+    assert_eq!(events.len(), SENDERS * MESSAGES);
+    assert_eq!(events, run(7));
+    assert_eq!(events, run(7));
+}
+
+#[test]
+fn test_seeds_reorder_messages() {
+    let first = run(0);
+    assert!((1..20).any(|seed| run(seed) != first));
+}
+
+#[test]
+fn test_single_thread() {
+    let sys = System::deterministic(1);
+    let main = thread::current().id();
+    let threads = Arc::new(Mutex::new(Vec::new()));
+
+    let threads2 = Arc::clone(&threads);
+    let _: Addr<Syn, _> = Arbiter::start(move |_| {
+        threads2.lock().unwrap().push(thread::current().id());
+        Collector(Arc::new(Mutex::new(Vec::new())))
+    });
+    let threads2 = Arc::clone(&threads);
+    Arbiter::new("test").do_send(actix::msgs::Execute::new(move || -> Result<(), ()> {
+        threads2.lock().unwrap().push(thread::current().id());
+        Arbiter::system().do_send(SystemExit(0));
+        Ok(())
+    }));
+    sys.run();
+
+    let threads = threads.lock().unwrap();
+    assert!(!threads.is_empty());
+    assert!(threads.iter().all(|id| *id == main));
+}
@@ -0,0 +1,148 @@
+extern crate actix;
+
+use std::thread;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use actix::prelude::*;
+use actix::msgs::SystemExit;
+
+struct RunInterval(Arc<AtomicUsize>, SpawnHandle);
+
+impl Actor for RunInterval {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.1 = ctx.run_interval(Duration::from_millis(10), |act, ctx| {
+            if act.0.fetch_add(1, Ordering::Relaxed) == 2 {
+                ctx.notify(Cancel);
+            }
+        });
+    }
+}
+
+struct Cancel;
+
+impl Message for Cancel {
+    type Result = ();
+}
+
+impl Handler<Cancel> for RunInterval {
+    type Result = ();
+
+    fn handle(&mut self, _: Cancel, ctx: &mut Self::Context) {
+        // whole series get cancelled
+        ctx.cancel_future(self.1);
+        ctx.run_later(Duration::from_millis(50), |_, _| {
+            Arbiter::system().do_send(SystemExit(0));
+        });
+    }
+}
+
+#[test]
+fn test_run_interval_cancel() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let _addr: Addr<Unsync, _> = RunInterval(Arc::clone(&count), SpawnHandle::default()).start();
+    sys.run();
+
+    assert_eq!(count.load(Ordering::Relaxed), 3);
+}
+
+struct Tick;
+
+impl Message for Tick {
+    type Result = ();
+}
+
+struct NotifyInterval(Arc<AtomicUsize>);
+
+impl Actor for NotifyInterval {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.notify_interval(|| Tick, Duration::from_millis(10));
+    }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        Arbiter::system().do_send(SystemExit(0));
+    }
+}
+
+impl Handler<Tick> for NotifyInterval {
+    type Result = ();
+
+    fn handle(&mut self, _: Tick, ctx: &mut Self::Context) {
+        if self.0.fetch_add(1, Ordering::Relaxed) == 4 {
+            ctx.stop();
+        }
+    }
+}
+
+#[test]
+fn test_notify_interval() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let _addr: Addr<Unsync, _> = NotifyInterval(Arc::clone(&count)).start();
+    sys.run();
+
+    assert_eq!(count.load(Ordering::Relaxed), 5);
+}
+
+struct Late {
+    policy: MissedTick,
+    count: usize,
+    start: Instant,
+    elapsed: Arc<Mutex<Option<Duration>>>,
+}
+
+impl Actor for Late {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.start = Instant::now();
+        ctx.run_interval_with(Duration::from_millis(20), self.policy, |act, ctx| {
+            act.count += 1;
+            if act.count == 1 {
+                // miss ticks at 40, 60, 80 and 100 ms
+                thread::sleep(Duration::from_millis(100));
+            } else if act.count == 5 {
+                *act.elapsed.lock().unwrap() = Some(act.start.elapsed());
+                ctx.stop();
+                Arbiter::system().do_send(SystemExit(0));
+            }
+        });
+    }
+}
+
+fn run_late(policy: MissedTick) -> Duration {
+    let sys = System::new("test");
+    let elapsed = Arc::new(Mutex::new(None));
+
+    let _addr: Addr<Unsync, _> = Late{
+        policy: policy, count: 0, start: Instant::now(), elapsed: Arc::clone(&elapsed)}.start();
+    sys.run();
+
+    let elapsed = elapsed.lock().unwrap().take();
+    elapsed.unwrap()
+}
+
+#[test]
+fn test_interval_burst() {
+    // missed ticks fire right after late tick
+    assert!(run_late(MissedTick::Burst) < Duration::from_millis(170));
+}
+
+#[test]
+fn test_interval_skip() {
+    // missed ticks are dropped, ticks at 140, 160, 180 and 200 ms
+    assert!(run_late(MissedTick::Skip) >= Duration::from_millis(190));
+}
+
+#[test]
+fn test_interval_delay() {
+    // schedule restarts after late tick
+    assert!(run_late(MissedTick::Delay) >= Duration::from_millis(190));
+}
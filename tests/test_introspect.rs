@@ -0,0 +1,119 @@
+#![cfg_attr(not(feature="introspect"), allow(dead_code))]
+extern crate actix;
+extern crate futures;
+
+use std::time::Duration;
+use futures::future;
+use actix::prelude::*;
+use actix::introspect::{Introspect, Topology};
+use actix::testkit::run_until;
+
+struct Ping;
+
+impl Message for Ping {
+    type Result = ();
+}
+
+struct Remote;
+
+impl Actor for Remote {
+    type Context = Context<Self>;
+}
+
+impl Handler<Ping> for Remote {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+/// Actor waits forever and never processes its mailbox
+struct Blocked;
+
+impl Actor for Blocked {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.spawn(actix::fut::wrap_future(future::empty()));
+        ctx.wait(actix::fut::wrap_future(future::empty()));
+    }
+}
+
+impl Handler<Ping> for Blocked {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+#[derive(Default)]
+struct SysService;
+
+impl Actor for SysService {
+    type Context = Context<Self>;
+}
+impl Supervised for SysService {}
+impl SystemService for SysService {}
+
+#[derive(Default)]
+struct ArbService;
+
+impl Actor for ArbService {
+    type Context = Context<Self>;
+}
+impl Supervised for ArbService {}
+impl ArbiterService for ArbService {}
+
+#[test]
+#[cfg(feature="introspect")]
+fn test_topology() {
+    let mut sys = System::new("test");
+
+    let blocked: Addr<Syn, _> = Blocked.start();
+    blocked.do_send(Ping);
+    blocked.do_send(Ping);
+    let _ = Arbiter::system_registry().get::<SysService>();
+    let _ = Arbiter::registry().get::<ArbService>();
+
+    let remote: Addr<Syn, _> = Arbiter::start(|_| Remote);
+    run_until(&mut sys, remote.send(Ping), Duration::from_secs(1)).unwrap();
+
+    let info = run_until(&mut sys, Arbiter::system().send(Topology),
+                         Duration::from_secs(1)).unwrap().unwrap();
+    assert_eq!(info.name, "test");
+    assert!(info.services.iter().any(|s| s.ends_with("SysService")));
+    assert_eq!(info.arbiters.len(), 2);
+
+    let system = &info.arbiters[0];
+    assert!(system.system);
+    assert!(system.services.iter().any(|s| s.ends_with("ArbService")));
+    let act = system.actors.as_ref().unwrap().iter().find(|a| a.id == blocked.id()).unwrap();
+    assert!(act.actor.ends_with("Blocked"));
+    assert_eq!(act.state, ActorState::Running);
+    assert_eq!(act.sync_queue, 2);
+    assert_eq!(act.spawned, 1);
+
+    let arbiter = &info.arbiters[1];
+    assert!(!arbiter.system);
+    assert_ne!(arbiter.thread, system.thread);
+    assert!(arbiter.actors.as_ref().unwrap().iter()
+            .any(|a| a.id == remote.id() && a.actor.ends_with("Remote")));
+
+    let dump = info.to_string();
+    assert!(dump.starts_with("System \"test\"\n"));
+    assert!(dump.contains(&format!("actor #{} ", blocked.id().into_usize())));
+}
+
+#[test]
+fn test_introspect_arbiter() {
+    let mut sys = System::new("test");
+
+    let arbiter = Arbiter::new("worker");
+    let info = run_until(&mut sys, arbiter.send(Introspect), Duration::from_secs(1)).unwrap();
+    assert!(!info.system);
+    assert!(info.name.contains("worker"));
+    if cfg!(feature="introspect") {
+        assert!(info.actors.unwrap().iter().any(|a| a.actor.ends_with("Arbiter")));
+    } else {
+        assert!(info.actors.is_none());
+        assert!(info.to_string().contains("actors unavailable"));
+    }
+}
@@ -90,18 +90,23 @@ fn test_active_address() {
         temp: None, restore_after_stop: false,
     }.start();
 
+    let stopping2 = Arc::clone(&stopping);
+    let stopped2 = Arc::clone(&stopped);
     Arbiter::handle().spawn(
         Timeout::new(Duration::new(0, 100), Arbiter::handle()).unwrap()
-            .then(|_| {
+            .then(move |_| {
+                assert!(!stopping2.load(Ordering::Relaxed), "Stopping");
+                assert!(!stopped2.load(Ordering::Relaxed), "Stopped");
                 Arbiter::system().do_send(SystemExit(0));
                 future::result(Ok(()))
             })
     );
 
     sys.run();
+    // running actor is stopped on system shutdown
     assert!(started.load(Ordering::Relaxed), "Not started");
-    assert!(!stopping.load(Ordering::Relaxed), "Stopping");
-    assert!(!stopped.load(Ordering::Relaxed), "Stopped");
+    assert!(stopping.load(Ordering::Relaxed), "Not stopping");
+    assert!(stopped.load(Ordering::Relaxed), "Not stopped");
 }
 
 #[test]
@@ -119,18 +124,23 @@ fn test_active_sync_address() {
         temp: None, restore_after_stop: false,
     }.start();
 
+    let stopping2 = Arc::clone(&stopping);
+    let stopped2 = Arc::clone(&stopped);
     Arbiter::handle().spawn(
         Timeout::new(Duration::new(0, 100), Arbiter::handle()).unwrap()
-            .then(|_| {
+            .then(move |_| {
+                assert!(!stopping2.load(Ordering::Relaxed), "Stopping");
+                assert!(!stopped2.load(Ordering::Relaxed), "Stopped");
                 Arbiter::system().do_send(SystemExit(0));
                 future::result(Ok(()))
             })
     );
 
     sys.run();
+    // running actor is stopped on system shutdown
     assert!(started.load(Ordering::Relaxed), "Not started");
-    assert!(!stopping.load(Ordering::Relaxed), "Stopping");
-    assert!(!stopped.load(Ordering::Relaxed), "Stopped");
+    assert!(stopping.load(Ordering::Relaxed), "Not stopping");
+    assert!(stopped.load(Ordering::Relaxed), "Not stopped");
 }
 
 #[test]
@@ -300,16 +310,20 @@ fn test_stop_restore_after_stopping() {
         temp: None, restore_after_stop: true,
     }.start();
 
+    let stopping2 = Arc::clone(&stopping);
+    let stopped2 = Arc::clone(&stopped);
     Arbiter::handle().spawn(
         Timeout::new(Duration::new(0, 100), Arbiter::handle()).unwrap()
-            .then(|_| {
+            .then(move |_| {
+                assert!(stopping2.load(Ordering::Relaxed), "Not stopping");
+                assert!(!stopped2.load(Ordering::Relaxed), "Stopped");
                 Arbiter::system().do_send(SystemExit(0));
                 future::result(Ok(()))
             })
     );
 
     sys.run();
+    // restored actor is stopped on system shutdown
     assert!(started.load(Ordering::Relaxed), "Not started");
-    assert!(stopping.load(Ordering::Relaxed), "Not stopping");
-    assert!(!stopped.load(Ordering::Relaxed), "Stopped");
+    assert!(stopped.load(Ordering::Relaxed), "Not stopped");
 }
@@ -0,0 +1,208 @@
+extern crate actix;
+extern crate futures;
+extern crate tokio_core;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use futures::{future, Future};
+use tokio_core::reactor::Timeout;
+use actix::prelude::*;
+use actix::msgs::{DeadLetter, DeadLetterReason};
+
+struct Msg(usize, u32);
+
+impl Message for Msg {
+    type Result = ();
+
+    fn priority(&self) -> u32 {
+        self.1
+    }
+}
+
+struct PriorityActor(Arc<Mutex<Vec<usize>>>);
+
+impl Actor for PriorityActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.set_mailbox_priority(true);
+    }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+impl Handler<Msg> for PriorityActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Msg, _: &mut Self::Context) {
+        self.0.lock().unwrap().push(msg.0);
+    }
+}
+
+#[test]
+fn test_unsync_priority() {
+    let sys = System::new("test");
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    let addr: Addr<Unsync, _> = PriorityActor(Arc::clone(&order)).start();
+    addr.do_send(Msg(1, 0));
+    addr.do_send(Msg(2, 0));
+    addr.do_send(Msg(3, 10));
+    addr.do_send(Msg(4, 5));
+    addr.do_send(Msg(5, 10));
+    drop(addr);
+
+    sys.run();
+    assert_eq!(*order.lock().unwrap(), vec![3, 5, 4, 1, 2]);
+}
+
+#[test]
+fn test_sync_priority() {
+    let sys = System::new("test");
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    let addr: Addr<Syn, _> = PriorityActor(Arc::clone(&order)).start();
+    addr.do_send(Msg(1, 0));
+    addr.do_send(Msg(2, 0));
+    addr.do_send(Msg(3, 10));
+    addr.do_send(Msg(4, 5));
+    addr.do_send(Msg(5, 10));
+    drop(addr);
+
+    sys.run();
+    assert_eq!(*order.lock().unwrap(), vec![3, 5, 4, 1, 2]);
+}
+
+struct OverflowActor {
+    policy: MailboxPolicy,
+    sync: bool,
+    order: Arc<Mutex<Vec<usize>>>,
+    rejected: Arc<AtomicUsize>,
+}
+
+impl Actor for OverflowActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.set_mailbox_capacity(2);
+        ctx.set_mailbox_policy(self.policy.clone());
+
+        for i in 1..6 {
+            let res = if self.sync {
+                let addr: Addr<Syn, _> = ctx.address();
+                addr.recipient().do_send(Msg(i, 0))
+            } else {
+                let addr: Addr<Unsync, _> = ctx.address();
+                addr.recipient().do_send(Msg(i, 0))
+            };
+            if res.is_err() {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Handler<Msg> for OverflowActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Msg, _: &mut Self::Context) {
+        self.order.lock().unwrap().push(msg.0);
+    }
+}
+
+struct DeadLetterCounter(Arc<AtomicUsize>);
+
+impl Actor for DeadLetterCounter {
+    type Context = Context<Self>;
+}
+
+impl Handler<DeadLetter> for DeadLetterCounter {
+    type Result = ();
+
+    fn handle(&mut self, msg: DeadLetter, _: &mut Self::Context) {
+        assert_eq!(msg.reason, DeadLetterReason::Dropped);
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn run_overflow<F>(sync: bool, policy: F) -> (Vec<usize>, usize)
+    where F: FnOnce() -> MailboxPolicy
+{
+    let sys = System::new("test");
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let rejected = Arc::new(AtomicUsize::new(0));
+
+    let _: () = OverflowActor {
+        policy: policy(),
+        sync: sync,
+        order: Arc::clone(&order),
+        rejected: Arc::clone(&rejected)}.start();
+
+    Arbiter::handle().spawn(
+        Timeout::new(Duration::new(0, 100_000_000), Arbiter::handle()).unwrap()
+            .then(|_| {
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                future::result(Ok(()))
+            })
+    );
+    sys.run();
+
+    let res = order.lock().unwrap().clone();
+    (res, rejected.load(Ordering::Relaxed))
+}
+
+#[test]
+fn test_policy_block() {
+    assert_eq!(run_overflow(false, || MailboxPolicy::Block), (vec![1, 2, 3, 4, 5], 0));
+}
+
+#[test]
+fn test_policy_block_sync() {
+    assert_eq!(run_overflow(true, || MailboxPolicy::Block), (vec![1, 2, 3, 4, 5], 0));
+}
+
+#[test]
+fn test_policy_drop_newest() {
+    assert_eq!(run_overflow(false, || MailboxPolicy::DropNewest), (vec![1, 2], 0));
+}
+
+#[test]
+fn test_policy_drop_newest_sync() {
+    assert_eq!(run_overflow(true, || MailboxPolicy::DropNewest), (vec![1, 2], 0));
+}
+
+#[test]
+fn test_policy_drop_oldest() {
+    assert_eq!(run_overflow(false, || MailboxPolicy::DropOldest), (vec![4, 5], 0));
+}
+
+#[test]
+fn test_policy_drop_oldest_sync() {
+    assert_eq!(run_overflow(true, || MailboxPolicy::DropOldest), (vec![4, 5], 0));
+}
+
+#[test]
+fn test_policy_reject() {
+    assert_eq!(run_overflow(false, || MailboxPolicy::Reject), (vec![1, 2], 3));
+}
+
+#[test]
+fn test_policy_reject_sync() {
+    assert_eq!(run_overflow(true, || MailboxPolicy::Reject), (vec![1, 2], 3));
+}
+
+#[test]
+fn test_policy_dead_letter() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let count2 = Arc::clone(&count);
+
+    let res = run_overflow(false, move || {
+        let addr: Addr<Syn, _> = DeadLetterCounter(count2).start();
+        MailboxPolicy::DeadLetter(addr.recipient())
+    });
+    assert_eq!(res, (vec![1, 2], 0));
+    assert_eq!(count.load(Ordering::Relaxed), 3);
+}
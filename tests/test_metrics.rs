@@ -0,0 +1,135 @@
+#![cfg(feature="metrics")]
+extern crate actix;
+extern crate futures;
+
+use std::time::Duration;
+use futures::{future, Future};
+use actix::prelude::*;
+use actix::metrics;
+use actix::testkit::run_until;
+
+struct Ping;
+
+impl Message for Ping {
+    type Result = ();
+}
+
+struct Die;
+
+impl Message for Die {
+    type Result = ();
+}
+
+struct Pinged;
+
+impl Actor for Pinged {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.spawn(actix::fut::wrap_future(future::ok(())));
+    }
+}
+
+impl Supervised for Pinged {}
+
+impl Handler<Ping> for Pinged {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+impl Handler<Die> for Pinged {
+    type Result = ();
+
+    fn handle(&mut self, _: Die, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+#[test]
+fn test_actor_metrics() {
+    let mut sys = System::new("test");
+
+    let addr: Addr<Syn, _> = Pinged.start();
+    for _ in 0..3 {
+        run_until(&mut sys, addr.send(Ping), Duration::from_secs(1)).unwrap();
+    }
+
+    let m = metrics::actor(addr.id()).unwrap();
+    assert!(m.actor.ends_with("Pinged"));
+    assert_eq!(m.received, 3);
+    assert_eq!(m.handled, 3);
+    assert_eq!(m.spawned, 1);
+    assert_eq!(m.restarts, 0);
+    assert_eq!(m.handlers.len(), 1);
+    assert!(m.handlers[0].message.ends_with("Ping"));
+    assert_eq!(m.handlers[0].time.count, 3);
+    assert_eq!(m.handlers[0].time.buckets.last().unwrap().1, 3);
+}
+
+#[test]
+fn test_restarts() {
+    let mut sys = System::new("test");
+
+    let addr: Addr<Syn, _> = Supervisor::start(|_| Pinged);
+    run_until(&mut sys, addr.send(Die).then(|_| addr.send(Ping)),
+              Duration::from_secs(1)).unwrap();
+
+    let m = metrics::actor(addr.id()).unwrap();
+    assert_eq!(m.restarts, 1);
+    assert_eq!(m.spawned, 2);
+    assert_eq!(m.handled, 2);
+}
+
+#[test]
+fn test_dropped_actor() {
+    let mut sys = System::new("test");
+
+    let addr: Addr<Syn, _> = Pinged.start();
+    let id = addr.id();
+    run_until(&mut sys, addr.send(Ping), Duration::from_secs(1)).unwrap();
+    assert!(metrics::actor(id).is_some());
+
+    drop(addr);
+    run_until(&mut sys, actix::clock::Delay::new(Duration::from_millis(10)),
+              Duration::from_secs(1)).unwrap();
+    assert!(metrics::actor(id).is_none());
+
+    // counters of stopped actor are kept
+    let stopped: Vec<_> = metrics::stopped().into_iter()
+        .filter(|m| m.actor.ends_with("Pinged"))
+        .collect();
+    assert_eq!(stopped.len(), 1);
+    assert_eq!(stopped[0].actors, 1);
+    assert_eq!(stopped[0].received, 1);
+    assert_eq!(stopped[0].handled, 1);
+    assert_eq!(stopped[0].handlers[0].time.count, 1);
+}
+
+struct Worker;
+
+impl Actor for Worker {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<Ping> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+}
+
+#[test]
+fn test_sync_metrics() {
+    let mut sys = System::new("test");
+
+    let addr = SyncArbiter::start(2, || Worker);
+    for _ in 0..4 {
+        run_until(&mut sys, addr.send(Ping), Duration::from_secs(1)).unwrap();
+    }
+
+    let workers: Vec<_> = metrics::snapshot().into_iter()
+        .filter(|m| m.actor.ends_with("Worker"))
+        .collect();
+    assert_eq!(workers.len(), 2);
+    assert_eq!(workers.iter().map(|m| m.handled).sum::<u64>(), 4);
+}
@@ -0,0 +1,81 @@
+extern crate actix;
+extern crate futures;
+extern crate tokio_core;
+extern crate tokio_io;
+
+use std::net::SocketAddr;
+use std::time::Duration;
+use futures::Future;
+use tokio_core::net::TcpStream;
+use tokio_io::io::{read_to_end, write_all};
+use actix::prelude::*;
+use actix::actors::metrics::{Counter, Gauge, MetricsExporter, Register};
+use actix::testkit::run_until;
+
+fn get(sys: &mut SystemRunner, addr: &SocketAddr, path: &str) -> String {
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    let fut = TcpStream::connect(addr, Arbiter::handle())
+        .and_then(move |stream| write_all(stream, request))
+        .and_then(|(stream, _)| read_to_end(stream, Vec::new()))
+        .map(|(_, resp)| String::from_utf8(resp).unwrap());
+    run_until(sys, fut, Duration::from_secs(5)).unwrap()
+}
+
+#[test]
+fn test_exporter() {
+    let mut sys = System::new("test");
+
+    let requests = Counter::new("requests_total", "Number of requests");
+    let connections = Gauge::new("connections", "Open connections");
+    requests.add(3);
+    connections.set(5);
+    connections.dec();
+
+    let exporter = MetricsExporter::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let local = exporter.local_addr();
+    let addr: Addr<Syn, _> = exporter.start();
+    run_until(&mut sys, addr.send(Register::Counter(requests.clone())),
+              Duration::from_secs(1)).unwrap();
+    run_until(&mut sys, addr.send(Register::Gauge(connections.clone())),
+              Duration::from_secs(1)).unwrap();
+
+    let resp = get(&mut sys, &local, "/metrics");
+    assert!(resp.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(resp.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+    assert!(resp.contains("# HELP requests_total Number of requests\n\
+                           # TYPE requests_total counter\n\
+                           requests_total 3\n"));
+    assert!(resp.contains("# TYPE connections gauge\nconnections 4\n"));
+
+    requests.inc();
+    let resp = get(&mut sys, &local, "/metrics?format=text");
+    assert!(resp.contains("\nrequests_total 4\n"));
+}
+
+#[test]
+fn test_not_found() {
+    let mut sys = System::new("test");
+
+    let exporter = MetricsExporter::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let local = exporter.local_addr();
+    let _: Addr<Syn, _> = exporter.start();
+
+    let resp = get(&mut sys, &local, "/");
+    assert!(resp.starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[cfg(feature="metrics")]
+#[test]
+fn test_actor_metrics() {
+    let mut sys = System::new("test");
+
+    let exporter = MetricsExporter::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let local = exporter.local_addr();
+    let _: Addr<Syn, _> = exporter.start();
+
+    let resp = get(&mut sys, &local, "/metrics");
+    assert!(resp.contains("# TYPE actix_actors gauge\n"));
+    assert!(resp.contains("actix_actors{arbiter=\""));
+    assert!(resp.contains("actor=\"actix::actors::metrics::MetricsExporter\"} 1\n"));
+    assert!(resp.contains("# TYPE actix_handler_seconds histogram\n"));
+}
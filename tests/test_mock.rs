@@ -0,0 +1,83 @@
+extern crate actix;
+extern crate futures;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use futures::Future;
+use actix::prelude::*;
+use actix::dev::MessageRecipient;
+use actix::testkit::MockRecipient;
+use actix::msgs::SystemExit;
+
+#[derive(Debug, PartialEq)]
+struct Sum(usize, usize);
+
+impl Message for Sum {
+    type Result = usize;
+}
+
+#[test]
+fn test_mock_send() {
+    let mock = MockRecipient::new(|msg: &Sum| if msg.0 > 0 { Some(msg.0 + msg.1) } else { None });
+    let recipient = mock.recipient();
+
+    assert!(recipient.do_send(Sum(1, 1)).is_ok());
+    assert_eq!(recipient.send(Sum(1, 2)).wait().unwrap(), 3);
+    match recipient.send(Sum(0, 2)).wait() {
+        Err(MailboxError::Closed) => (),
+        _ => panic!("Request has to fail"),
+    }
+    assert_eq!(mock.len(), 3);
+    assert_eq!(mock.take(), vec![Sum(1, 1), Sum(1, 2), Sum(0, 2)]);
+    assert!(mock.is_empty());
+}
+
+#[test]
+fn test_mock_close() {
+    let mock = MockRecipient::<Sum>::with_default();
+    let recipient = mock.recipient();
+    let weak = recipient.downgrade();
+
+    mock.close();
+    assert!(recipient.do_send(Sum(1, 1)).is_err());
+    match recipient.send(Sum(1, 2)).wait() {
+        Err(MailboxError::Closed) => (),
+        _ => panic!("Request has to fail"),
+    }
+    assert!(mock.is_empty());
+
+    drop(recipient);
+    drop(mock);
+    assert!(weak.upgrade().is_none());
+}
+
+// Actor under test does not depend on transport of its recipient
+struct Adder<T: MessageRecipient<Sum>>(Recipient<T, Sum>, Rc<Cell<usize>>);
+
+impl<T: MessageRecipient<Sum> + 'static> Actor for Adder<T> {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.0.send(Sum(2, 3))
+            .into_actor(self)
+            .map(|res, act, _| {
+                act.1.set(res);
+                Arbiter::system().do_send(SystemExit(0));
+            })
+            .map_err(|_, _, _| ())
+            .wait(ctx);
+    }
+}
+
+#[test]
+fn test_mock_actor() {
+    let sys = System::new("test");
+    let mock = MockRecipient::new(|msg: &Sum| Some(msg.0 * msg.1));
+    let result = Rc::new(Cell::new(0));
+
+    let _: () = Adder(mock.recipient(), Rc::clone(&result)).start();
+    sys.run();
+
+    assert_eq!(result.get(), 6);
+    assert_eq!(mock.take(), vec![Sum(2, 3)]);
+}
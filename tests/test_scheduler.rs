@@ -0,0 +1,110 @@
+extern crate actix;
+extern crate futures;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use futures::Future;
+use actix::prelude::*;
+use actix::msgs::SystemExit;
+use actix::actors::scheduler::{Cancel, CronError, CronExpr, Schedule, ScheduleId, Scheduler};
+
+// 2018-03-01T00:00:00Z, Thursday
+const NOW: u64 = 1_519_862_400;
+
+fn next(cron: &str, offset: i32) -> Option<u64> {
+    cron.parse::<CronExpr>().unwrap().next_after(NOW, offset)
+}
+
+#[test]
+fn test_cron_next() {
+    assert_eq!(next("0 */5 * * * *", 0), Some(NOW + 300));
+    assert_eq!(next("*/5 * * * *", 0), Some(NOW + 300));
+    assert_eq!(next("* * * * * *", 0), Some(NOW + 1));
+    // 03:00 UTC+1
+    assert_eq!(next("0 0 3 * * *", 3600), Some(NOW + 2 * 3600));
+    // 13th or any Friday
+    assert_eq!(next("0 0 0 13 * 5", 0), Some(NOW + 86_400));
+    // Sunday
+    assert_eq!(next("0 0 0 * * 7", 0), next("0 0 0 * * 0", 0));
+    // 2020-02-29
+    assert_eq!(next("0 0 0 29 2 *", 0), Some(1_582_934_400));
+    assert_eq!(next("0 0 0 30 2 *", 0), None);
+}
+
+#[test]
+fn test_cron_parse_error() {
+    assert_eq!("* * *".parse::<CronExpr>(), Err(CronError::Fields));
+    assert_eq!("61 * * * * *".parse::<CronExpr>(), Err(CronError::Field("61".to_owned())));
+    assert_eq!("* 5-1 * * * *".parse::<CronExpr>(), Err(CronError::Field("5-1".to_owned())));
+    assert_eq!("*/0 * * * * *".parse::<CronExpr>(), Err(CronError::Field("*/0".to_owned())));
+}
+
+struct Tick;
+
+impl Message for Tick {
+    type Result = ();
+}
+
+struct Job {
+    count: Arc<AtomicUsize>,
+    id: Option<ScheduleId>,
+}
+
+impl Actor for Job {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let addr: Addr<Syn, _> = ctx.address();
+        Scheduler::from_registry()
+            .send(Schedule::new("* * * * * *", addr.recipient(), || Tick))
+            .into_actor(self)
+            .map(|res, act, _| act.id = res.ok())
+            .map_err(|_, _, _| ())
+            .wait(ctx);
+    }
+}
+
+impl Handler<Tick> for Job {
+    type Result = ();
+
+    fn handle(&mut self, _: Tick, ctx: &mut Self::Context) {
+        if self.count.fetch_add(1, Ordering::Relaxed) == 0 {
+            if let Some(id) = self.id {
+                Scheduler::from_registry().do_send(Cancel(id));
+            }
+            // no more ticks after cancel
+            ctx.run_later(Duration::from_millis(1500), |_, _| {
+                Arbiter::system().do_send(SystemExit(0));
+            });
+        }
+    }
+}
+
+#[test]
+fn test_scheduler() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let _addr: Addr<Syn, _> = Job{count: Arc::clone(&count), id: None}.start();
+    sys.run();
+
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_scheduler_never_matches() {
+    let sys = System::new("test");
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let addr: Addr<Syn, _> = Job{count: Arc::clone(&count), id: None}.start();
+    Arbiter::handle().spawn(
+        Scheduler::from_registry()
+            .send(Schedule::new("0 0 0 30 2 *", addr.recipient(), || Tick))
+            .then(|res| {
+                assert_eq!(res.unwrap().err(), Some(CronError::NeverMatches));
+                Arbiter::system().do_send(SystemExit(0));
+                Ok(())
+            }));
+    sys.run();
+}
@@ -2,12 +2,16 @@ extern crate actix;
 extern crate futures;
 extern crate tokio_core;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use futures::{future, Future};
 use tokio_core::reactor::Timeout;
 use actix::prelude::*;
+use actix::{GiveUp, RestartPolicy};
+use actix::clock::TestClock;
+use actix::msgs::Terminated;
+use actix::actors::supervisor_tree::{SupervisorTree, Strategy};
 
 struct Die;
 
@@ -70,3 +74,228 @@ fn test_supervisor_restart() {
     assert_eq!(restarts.load(Ordering::Relaxed), 2);
     assert_eq!(messages.load(Ordering::Relaxed), 2);
 }
+
+fn exit_after(ms: u64) {
+    Arbiter::handle().spawn(
+        Timeout::new(Duration::from_millis(ms), Arbiter::handle()).unwrap()
+            .then(|_| {
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                future::result(Ok(()))
+            })
+    );
+}
+
+/// Advances virtual clock by 100ms per step, records actor starts on each step
+struct Stepper(TestClock, Arc<AtomicUsize>, Arc<Mutex<Vec<usize>>>);
+
+impl Stepper {
+    fn step(&mut self, ctx: &mut Context<Self>) {
+        let steps = {
+            let mut starts = self.2.lock().unwrap();
+            starts.push(self.1.load(Ordering::Relaxed));
+            starts.len()
+        };
+        if steps == 10 {
+            Arbiter::system().do_send(actix::msgs::SystemExit(0));
+        } else {
+            self.0.advance(Duration::from_millis(100));
+            ctx.run_later(Duration::new(0, 0), |act, ctx| act.step(ctx));
+        }
+    }
+}
+
+impl Actor for Stepper {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::new(0, 0), |act, ctx| act.step(ctx));
+    }
+}
+
+#[test]
+fn test_supervisor_backoff() {
+    let sys = System::new("test");
+    let clock = System::test_clock();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let restarts = Arc::new(AtomicUsize::new(0));
+    let messages = Arc::new(AtomicUsize::new(0));
+    let starts2 = Arc::clone(&starts);
+    let restarts2 = Arc::clone(&restarts);
+    let messages2 = Arc::clone(&messages);
+    let steps = Arc::new(Mutex::new(Vec::new()));
+
+    let policy = RestartPolicy::default()
+        .backoff(Duration::from_millis(500), Duration::from_secs(1));
+    let addr: Addr<Unsync, _> = actix::Supervisor::start_with(
+        policy, move |_| MyActor(starts2, restarts2, messages2));
+    addr.do_send(Die);
+    addr.do_send(Die);
+
+    let _: () = Stepper(clock, Arc::clone(&starts), Arc::clone(&steps)).start();
+    sys.run();
+
+    // second message waits until actor restarts after 500ms,
+    // next restart is delayed by 1s
+    let steps = steps.lock().unwrap();
+    assert_eq!(&steps[1..5], &[1, 1, 1, 1]);
+    assert_eq!(steps[9], 2);
+    assert_eq!(starts.load(Ordering::Relaxed), 2);
+    assert_eq!(restarts.load(Ordering::Relaxed), 1);
+    assert_eq!(messages.load(Ordering::Relaxed), 2);
+}
+
+struct Watcher(Addr<Syn, MyActor>, Arc<Mutex<Vec<StopReason>>>);
+
+impl Actor for Watcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.watch(&self.0);
+        self.0.do_send(Die);
+        self.0.do_send(Die);
+        self.0.do_send(Die);
+    }
+}
+
+impl Handler<Terminated> for Watcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, _: &mut Self::Context) {
+        self.1.lock().unwrap().push(msg.reason);
+    }
+}
+
+#[test]
+fn test_supervisor_give_up() {
+    let sys = System::new("test");
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let restarts = Arc::new(AtomicUsize::new(0));
+    let messages = Arc::new(AtomicUsize::new(0));
+    let starts2 = Arc::clone(&starts);
+    let restarts2 = Arc::clone(&restarts);
+    let messages2 = Arc::clone(&messages);
+    let reasons = Arc::new(Mutex::new(Vec::new()));
+
+    let policy = RestartPolicy::default()
+        .max_restarts(1, Duration::from_secs(60))
+        .give_up(GiveUp::Escalate);
+    let addr: Addr<Syn, _> = actix::Supervisor::start_with(
+        policy, move |_| MyActor(starts2, restarts2, messages2));
+    let _: () = Watcher(addr, Arc::clone(&reasons)).start();
+
+    exit_after(100);
+    sys.run();
+
+    assert_eq!(starts.load(Ordering::Relaxed), 2);
+    assert_eq!(restarts.load(Ordering::Relaxed), 1);
+    assert_eq!(messages.load(Ordering::Relaxed), 2);
+    assert_eq!(*reasons.lock().unwrap(), vec![StopReason::Escalated]);
+}
+
+struct Child {
+    starts: Arc<AtomicUsize>,
+    fail: bool,
+}
+
+impl Actor for Child {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // fail on first start only
+        if self.starts.fetch_add(1, Ordering::Relaxed) == 0 && self.fail {
+            ctx.stop_with(StopReason::Error("failed".to_owned()));
+        }
+    }
+}
+
+fn run_tree(strategy: Strategy) -> (usize, usize, usize) {
+    let sys = System::new("test");
+
+    let first = Arc::new(AtomicUsize::new(0));
+    let second = Arc::new(AtomicUsize::new(0));
+    let third = Arc::new(AtomicUsize::new(0));
+    let (first2, second2, third2) = (Arc::clone(&first), Arc::clone(&second), Arc::clone(&third));
+
+    let _tree: Addr<Syn, _> = SupervisorTree::new(strategy)
+        .child("first", move || Child{starts: Arc::clone(&first2), fail: false}.start())
+        .child("second", move || Child{starts: Arc::clone(&second2), fail: true}.start())
+        .child("nested", move || {
+            let third2 = Arc::clone(&third2);
+            SupervisorTree::new(Strategy::OneForOne)
+                .child("third", move || Child{starts: Arc::clone(&third2), fail: false}.start())
+                .start()
+        })
+        .start();
+
+    exit_after(100);
+    sys.run();
+
+    (first.load(Ordering::Relaxed),
+     second.load(Ordering::Relaxed),
+     third.load(Ordering::Relaxed))
+}
+
+#[test]
+fn test_tree_one_for_one() {
+    assert_eq!(run_tree(Strategy::OneForOne), (1, 2, 1));
+}
+
+#[test]
+fn test_tree_one_for_all() {
+    assert_eq!(run_tree(Strategy::OneForAll), (2, 2, 2));
+}
+
+#[test]
+fn test_tree_rest_for_one() {
+    assert_eq!(run_tree(Strategy::RestForOne), (1, 2, 2));
+}
+
+struct Panic;
+
+impl Message for Panic {
+    type Result = ();
+}
+
+struct PanicActor(Arc<Mutex<Vec<StopReason>>>);
+
+impl Actor for PanicActor {
+    type Context = Context<Self>;
+}
+
+impl actix::Supervised for PanicActor {
+    fn restarting(&mut self, ctx: &mut Context<Self>) {
+        self.0.lock().unwrap().push(ctx.stop_reason());
+    }
+}
+
+impl Handler<Panic> for PanicActor {
+    type Result = ();
+
+    fn handle(&mut self, _: Panic, _: &mut Self::Context) {
+        panic!("boom");
+    }
+}
+
+impl Handler<Die> for PanicActor {
+    type Result = ();
+
+    fn handle(&mut self, _: Die, _: &mut Self::Context) {
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+#[test]
+fn test_supervisor_panic() {
+    let sys = System::new("test");
+    let reasons = Arc::new(Mutex::new(Vec::new()));
+    let reasons2 = Arc::clone(&reasons);
+
+    let addr: Addr<Unsync, _> = actix::Supervisor::start(move |_| PanicActor(reasons2));
+    addr.do_send(Panic);
+    addr.do_send(Die);
+    sys.run();
+
+    assert_eq!(*reasons.lock().unwrap(), vec![StopReason::Panicked("boom".to_owned())]);
+}
@@ -4,7 +4,7 @@ extern crate tokio_core;
 
 use std::sync::{Arc, Condvar, Mutex};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use futures::future;
+use futures::{future, Future};
 use actix::prelude::*;
 
 
@@ -101,3 +101,75 @@ fn test_sync() {
     assert_eq!(counter.load(Ordering::Relaxed), 2, "Not started");
     assert_eq!(messages.load(Ordering::Relaxed), 5, "Wrong number of messages");
 }
+
+struct Divide(u32);
+
+impl Message for Divide {
+    type Result = u32;
+}
+
+struct Divider;
+
+impl Actor for Divider {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<Divide> for Divider {
+    type Result = u32;
+
+    fn handle(&mut self, msg: Divide, _: &mut Self::Context) -> u32 {
+        if msg.0 == 0 {
+            panic!("division by zero");
+        }
+        100 / msg.0
+    }
+}
+
+#[test]
+fn test_sync_panic() {
+    let sys = System::new("test");
+    let (addr, restarts) = SyncArbiter::start_with_counter(1, || Divider);
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let results2 = Arc::clone(&results);
+    let results3 = Arc::clone(&results);
+
+    Arbiter::handle().spawn(
+        addr.send(Divide(0))
+            .then(move |res| {
+                results2.lock().unwrap().push(res.map_err(|e| format!("{}", e)));
+                addr.send(Divide(4))
+            })
+            .then(move |res| {
+                results3.lock().unwrap().push(res.map_err(|e| format!("{}", e)));
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                future::result(Ok(()))
+            }));
+
+    sys.run();
+    assert_eq!(restarts.get(), 1);
+    assert_eq!(*results.lock().unwrap(),
+               vec![Err("Message handler panicked".to_owned()), Ok(25)]);
+}
+
+#[test]
+fn test_sync_factory_panic() {
+    let sys = System::new("test");
+    let (addr, restarts) = SyncArbiter::start_with_counter(1, || -> Divider {
+        panic!("can not create actor")
+    });
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let results2 = Arc::clone(&results);
+
+    Arbiter::handle().spawn(
+        addr.send(Divide(4))
+            .then(move |res| {
+                results2.lock().unwrap().push(res.map_err(|e| format!("{}", e)));
+                Arbiter::system().do_send(actix::msgs::SystemExit(0));
+                future::result(Ok(()))
+            }));
+
+    // worker stops after restart limit is exceeded
+    sys.run();
+    assert_eq!(restarts.get(), 11);
+    assert_eq!(*results.lock().unwrap(), vec![Err("Mailbox has closed".to_owned())]);
+}
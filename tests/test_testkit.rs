@@ -0,0 +1,128 @@
+extern crate actix;
+
+use std::time::{Duration, Instant};
+use actix::prelude::*;
+use actix::clock::TestClock;
+use actix::testkit::{run_until, TestProbe};
+
+#[derive(Debug, PartialEq)]
+struct Ping(usize);
+
+impl Message for Ping {
+    type Result = ();
+}
+
+struct Pinger(Recipient<Syn, Ping>, usize);
+
+impl Actor for Pinger {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        for i in 0..self.1 {
+            let _ = self.0.do_send(Ping(i));
+        }
+    }
+}
+
+#[test]
+fn test_expect_msg() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::new();
+
+    let _: () = Pinger(probe.recipient(), 1).start();
+
+    assert_eq!(probe.expect_msg(&mut sys), Ping(0));
+    probe.expect_no_msg(&mut sys, Duration::from_millis(50));
+}
+
+#[test]
+fn test_receive_n() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::new();
+
+    let recipient = probe.recipient();
+    let _addr: Addr<Syn, _> = Arbiter::start(move |_| Pinger(recipient, 3));
+
+    assert_eq!(probe.receive_n(&mut sys, 3), vec![Ping(0), Ping(1), Ping(2)]);
+}
+
+#[test]
+#[should_panic(expected = "is not received")]
+fn test_expect_msg_timeout() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::<Ping>::new().timeout(Duration::from_millis(50));
+
+    probe.expect_msg(&mut sys);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected message")]
+fn test_expect_no_msg() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::new();
+
+    let _: () = Pinger(probe.recipient(), 1).start();
+    probe.expect_no_msg(&mut sys, Duration::from_millis(50));
+}
+
+struct Advance(TestClock);
+
+impl Actor for Advance {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        self.0.advance(Duration::from_secs(3600));
+    }
+}
+
+#[test]
+fn test_virtual_timeout() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::<Ping>::new();
+    let start = Instant::now();
+
+    let _: () = Advance(System::test_clock()).start();
+    probe.expect_no_msg(&mut sys, Duration::from_secs(3600));
+    assert!(start.elapsed() < Duration::from_secs(3600));
+}
+
+struct Echo;
+
+impl Actor for Echo {
+    type Context = Context<Self>;
+}
+
+struct Hello(&'static str);
+
+impl Message for Hello {
+    type Result = String;
+}
+
+impl Handler<Hello> for Echo {
+    type Result = String;
+
+    fn handle(&mut self, msg: Hello, _: &mut Self::Context) -> String {
+        if msg.0.is_empty() {
+            panic!("empty name");
+        }
+        format!("hello {}", msg.0)
+    }
+}
+
+#[test]
+fn test_run_until() {
+    let mut sys = System::new("test");
+
+    let addr: Addr<Syn, _> = Echo.start();
+    let res = run_until(&mut sys, addr.send(Hello("world")), Duration::from_secs(1));
+    assert_eq!(res.unwrap(), "hello world");
+}
+
+#[test]
+#[should_panic(expected = "empty name")]
+fn test_actor_panic() {
+    let mut sys = System::new("test");
+
+    let addr: Addr<Syn, _> = Echo.start();
+    let _ = run_until(&mut sys, addr.send(Hello("")), Duration::from_secs(1));
+}
@@ -0,0 +1,127 @@
+extern crate actix;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use actix::prelude::*;
+use actix::clock::TestClock;
+use actix::msgs::SystemExit;
+
+struct Timers(TestClock, Arc<Mutex<Vec<u64>>>);
+
+impl Actor for Timers {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let mut handles = Vec::new();
+        for i in 0..1000u64 {
+            let ms = (i * 7919) % 100;
+            handles.push(ctx.run_later(Duration::from_millis(ms), move |act, _| {
+                act.1.lock().unwrap().push(ms);
+            }));
+        }
+        // cancel every other timer
+        for (idx, handle) in handles.into_iter().enumerate() {
+            if idx % 2 == 0 {
+                ctx.cancel_future(handle);
+            }
+        }
+        ctx.run_later(Duration::from_millis(200), |_, _| {
+            Arbiter::system().do_send(SystemExit(0));
+        });
+        self.0.advance(Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn test_timers_cancel() {
+    let sys = System::new("test");
+    let fired = Arc::new(Mutex::new(Vec::new()));
+
+    let _: () = Timers(System::test_clock(), Arc::clone(&fired)).start();
+    sys.run();
+
+    // only timers that were not cancelled fire
+    let mut fired = fired.lock().unwrap().clone();
+    fired.sort();
+    let mut expected: Vec<_> = (0..1000u64).filter(|i| i % 2 == 1).map(|i| (i * 7919) % 100).collect();
+    expected.sort();
+    assert_eq!(fired, expected);
+}
+
+struct Delayed(u64, Arc<Mutex<Vec<u64>>>);
+
+impl Actor for Delayed {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::from_millis(self.0), |act, _| {
+            act.1.lock().unwrap().push(act.0);
+        });
+    }
+}
+
+struct Advance(TestClock);
+
+impl Actor for Advance {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::from_millis(200), |_, _| {
+            Arbiter::system().do_send(SystemExit(0));
+        });
+        // all deadlines expire at once
+        self.0.advance(Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn test_timers_order() {
+    let sys = System::new("test");
+    let clock = System::test_clock();
+    let fired = Arc::new(Mutex::new(Vec::new()));
+
+    for i in 0..100u64 {
+        let _: () = Delayed((i * 7919) % 100, Arc::clone(&fired)).start();
+    }
+    let _: () = Advance(clock).start();
+    sys.run();
+
+    // actors are polled in deadline order of their timers
+    let fired = fired.lock().unwrap().clone();
+    let mut expected: Vec<_> = (0..100u64).map(|i| (i * 7919) % 100).collect();
+    expected.sort();
+    assert_eq!(fired, expected);
+}
+
+struct LongTimers(TestClock, Arc<Mutex<Vec<u64>>>);
+
+impl Actor for LongTimers {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // timers on different levels of the wheel
+        for &secs in &[86_400 * 1000, 86_400 * 10, 3600, 60, 1] {
+            ctx.run_later(Duration::from_secs(secs), move |act, _| {
+                act.1.lock().unwrap().push(secs);
+            });
+        }
+        ctx.run_later(Duration::from_secs(86_400 * 1000 + 1), |_, _| {
+            Arbiter::system().do_send(SystemExit(0));
+        });
+        ctx.run_interval(Duration::from_secs(1), |act, _| {
+            act.0.advance(Duration::from_secs(3600));
+        });
+        self.0.advance(Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn test_long_timers() {
+    let sys = System::new("test");
+    let fired = Arc::new(Mutex::new(Vec::new()));
+
+    let _: () = LongTimers(System::test_clock(), Arc::clone(&fired)).start();
+    sys.run();
+
+    assert_eq!(*fired.lock().unwrap(), vec![1, 60, 3600, 86_400 * 10, 86_400 * 1000]);
+}
@@ -0,0 +1,114 @@
+extern crate actix;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use actix::prelude::*;
+use actix::trace::{self, TraceContext};
+use actix::testkit::run_until;
+
+type Traces = Arc<Mutex<Vec<Option<TraceContext>>>>;
+
+struct Hop(usize);
+
+impl Message for Hop {
+    type Result = ();
+}
+
+struct Node {
+    traces: Traces,
+    next: Option<Recipient<Syn, Hop>>,
+}
+
+impl Actor for Node {
+    type Context = Context<Self>;
+}
+
+impl Handler<Hop> for Node {
+    type Result = ();
+
+    fn handle(&mut self, msg: Hop, ctx: &mut Self::Context) {
+        self.traces.lock().unwrap().push(ctx.trace_context());
+        if let Some(ref next) = self.next {
+            let _ = next.do_send(Hop(msg.0 + 1));
+        }
+    }
+}
+
+struct Worker(Traces);
+
+impl Actor for Worker {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<Hop> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _: Hop, ctx: &mut Self::Context) {
+        self.0.lock().unwrap().push(ctx.trace_context());
+    }
+}
+
+fn wait_for(sys: &mut SystemRunner, traces: &Traces, n: usize) {
+    for _ in 0..100 {
+        if traces.lock().unwrap().len() >= n {
+            return
+        }
+        run_until(sys, actix::clock::Delay::new(Duration::from_millis(10)),
+                  Duration::from_secs(1)).unwrap();
+    }
+    panic!("Messages are not delivered");
+}
+
+#[test]
+fn test_propagation() {
+    let mut sys = System::new("test");
+    let traces = Arc::new(Mutex::new(Vec::new()));
+
+    let worker = {
+        let traces = Arc::clone(&traces);
+        SyncArbiter::start(1, move || Worker(Arc::clone(&traces)))
+    };
+    let traces2 = Arc::clone(&traces);
+    let second: Addr<Syn, _> = Arbiter::start(
+        move |_| Node{traces: traces2, next: Some(worker.recipient())});
+    let first: Addr<Syn, _> = Node{
+        traces: Arc::clone(&traces), next: Some(second.recipient())}.start();
+
+    let root = TraceContext::new();
+    trace::scope(root, || first.do_send(Hop(0)));
+    assert_eq!(trace::current(), None);
+
+    wait_for(&mut sys, &traces, 3);
+
+    let traces: Vec<_> = traces.lock().unwrap().iter().map(|t| t.unwrap()).collect();
+    assert!(traces.iter().all(|t| t.trace_id() == root.trace_id()));
+    assert_eq!(traces[0].parent_id(), Some(root.span_id()));
+    assert_eq!(traces[1].parent_id(), Some(traces[0].span_id()));
+    assert_eq!(traces[2].parent_id(), Some(traces[1].span_id()));
+    assert_ne!(traces[0].span_id(), traces[1].span_id());
+}
+
+#[test]
+fn test_untraced() {
+    let mut sys = System::new("test");
+    let traces = Arc::new(Mutex::new(Vec::new()));
+
+    let addr: Addr<Unsync, _> = Node{traces: Arc::clone(&traces), next: None}.start();
+    run_until(&mut sys, addr.send(Hop(0)), Duration::from_secs(1)).unwrap();
+    run_until(&mut sys, trace::scope(TraceContext::new(), || addr.send(Hop(0))),
+              Duration::from_secs(1)).unwrap();
+
+    let traces = traces.lock().unwrap();
+    assert_eq!(traces[0], None);
+    assert!(traces[1].is_some());
+}
+
+#[test]
+fn test_display() {
+    let trace = TraceContext::from_ids(1, 0xff, Some(2));
+    assert_eq!(trace.to_string(),
+               "trace_id=0000000000000001 span_id=00000000000000ff parent_id=0000000000000002");
+    let child = trace.child();
+    assert_eq!(child.trace_id(), 1);
+    assert_eq!(child.parent_id(), Some(0xff));
+}
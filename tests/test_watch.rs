@@ -0,0 +1,325 @@
+extern crate actix;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use actix::prelude::*;
+use actix::msgs::Terminated;
+
+struct Worker;
+
+impl Actor for Worker {
+    type Context = Context<Self>;
+}
+
+struct Stop;
+
+impl Message for Stop {
+    type Result = ();
+}
+
+impl Handler<Stop> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+struct Watcher {
+    worker: Addr<Syn, Worker>,
+    terminated: Arc<Mutex<Vec<(ActorId, StopReason)>>>,
+}
+
+impl Actor for Watcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // watcher has no address, timer keeps it alive
+        ctx.run_later(Duration::from_secs(5), |_, _| ());
+        ctx.watch(&self.worker);
+        self.worker.do_send(Stop);
+    }
+}
+
+impl Handler<Terminated> for Watcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, _: &mut Self::Context) {
+        self.terminated.lock().unwrap().push((msg.id, msg.reason));
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+#[test]
+fn test_watch() {
+    let sys = System::new("test");
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Worker.start();
+    let id = worker.id();
+    let _: () = Watcher{worker: worker, terminated: Arc::clone(&terminated)}.start();
+    sys.run();
+
+    assert_eq!(*terminated.lock().unwrap(), vec![(id, StopReason::Normal)]);
+}
+
+#[test]
+fn test_watch_other_arbiter() {
+    let sys = System::new("test");
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Arbiter::start(|_| Worker);
+    let id = worker.id();
+    let _: () = Watcher{worker: worker, terminated: Arc::clone(&terminated)}.start();
+    sys.run();
+
+    assert_eq!(*terminated.lock().unwrap(), vec![(id, StopReason::Normal)]);
+}
+
+struct LateWatcher(Addr<Syn, Worker>, Arc<Mutex<Vec<(ActorId, StopReason)>>>);
+
+impl Actor for LateWatcher {
+    type Context = Context<Self>;
+}
+
+impl Handler<Terminated> for LateWatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, _: &mut Self::Context) {
+        self.1.lock().unwrap().push((msg.id, msg.reason));
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+impl Handler<Stop> for LateWatcher {
+    type Result = ();
+
+    fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+        // worker is stopped already
+        ctx.watch(&self.0);
+    }
+}
+
+#[test]
+fn test_watch_stopped() {
+    let sys = System::new("test");
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Worker.start();
+    let id = worker.id();
+    worker.do_send(Stop);
+
+    let watcher: Addr<Syn, _> = LateWatcher(worker, Arc::clone(&terminated)).start();
+    watcher.do_send(Stop);
+    sys.run();
+
+    assert_eq!(*terminated.lock().unwrap(), vec![(id, StopReason::Normal)]);
+}
+
+struct Fail;
+
+impl Message for Fail {
+    type Result = ();
+}
+
+impl Handler<Fail> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _: Fail, ctx: &mut Self::Context) {
+        ctx.stop_with(StopReason::Error("failed".to_owned()));
+    }
+}
+
+struct Session {
+    worker: Addr<Syn, Worker>,
+    trap: bool,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Actor for Session {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::from_secs(5), |_, _| ());
+        if self.trap {
+            ctx.trap_exit();
+        }
+        ctx.link(&self.worker);
+        self.worker.do_send(Fail);
+    }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        self.events.lock().unwrap().push("stopped".to_owned());
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+impl Handler<Terminated> for Session {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, _: &mut Self::Context) {
+        self.events.lock().unwrap().push(format!("{:?}", msg.reason));
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+#[test]
+fn test_link() {
+    let sys = System::new("test");
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Worker.start();
+    let _: () = Session{
+        worker: worker, trap: false, events: Arc::clone(&events)}.start();
+    sys.run();
+
+    assert_eq!(*events.lock().unwrap(), vec!["stopped".to_owned()]);
+}
+
+#[test]
+fn test_link_trap_exit() {
+    let sys = System::new("test");
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Worker.start();
+    let _: () = Session{
+        worker: worker, trap: true, events: Arc::clone(&events)}.start();
+    sys.run();
+
+    assert_eq!(events.lock().unwrap()[0], "Error(\"failed\")");
+}
+
+struct Panic;
+
+impl Message for Panic {
+    type Result = ();
+}
+
+impl Handler<Panic> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _: Panic, _: &mut Self::Context) {
+        panic!("boom");
+    }
+}
+
+struct PanicWatcher(Addr<Syn, Worker>, Arc<Mutex<Vec<(ActorId, StopReason)>>>);
+
+impl Actor for PanicWatcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::from_secs(5), |_, _| ());
+        ctx.watch(&self.0);
+        self.0.do_send(Panic);
+    }
+}
+
+impl Handler<Terminated> for PanicWatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, _: &mut Self::Context) {
+        self.1.lock().unwrap().push((msg.id, msg.reason));
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+#[test]
+fn test_watch_panic() {
+    let sys = System::new("test");
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Worker.start();
+    let id = worker.id();
+    let _: () = PanicWatcher(worker, Arc::clone(&terminated)).start();
+    sys.run();
+
+    // watcher runs in the same arbiter
+    assert_eq!(*terminated.lock().unwrap(),
+               vec![(id, StopReason::Panicked("boom".to_owned()))]);
+}
+
+struct DropWatcher(Option<Addr<Syn, Worker>>, Arc<Mutex<Vec<(ActorId, StopReason)>>>);
+
+impl Actor for DropWatcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_later(Duration::from_secs(5), |_, _| ());
+        // worker stops when last address is dropped
+        if let Some(worker) = self.0.take() {
+            ctx.watch(&worker);
+        }
+    }
+}
+
+impl Handler<Terminated> for DropWatcher {
+    type Result = ();
+
+    fn handle(&mut self, msg: Terminated, _: &mut Self::Context) {
+        self.1.lock().unwrap().push((msg.id, msg.reason));
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+#[test]
+fn test_watch_disconnected() {
+    let sys = System::new("test");
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+
+    let worker: Addr<Syn, _> = Worker.start();
+    let id = worker.id();
+    let _: () = DropWatcher(Some(worker), Arc::clone(&terminated)).start();
+    sys.run();
+
+    assert_eq!(*terminated.lock().unwrap(), vec![(id, StopReason::Disconnected)]);
+}
+
+struct Reasons(Arc<Mutex<Vec<StopReason>>>);
+
+impl Actor for Reasons {
+    type Context = Context<Self>;
+
+    fn stopping(&mut self, ctx: &mut Self::Context) -> bool {
+        self.0.lock().unwrap().push(ctx.stop_reason());
+        true
+    }
+
+    fn stopped(&mut self, ctx: &mut Self::Context) {
+        self.0.lock().unwrap().push(ctx.stop_reason());
+        Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    }
+}
+
+impl Handler<Fail> for Reasons {
+    type Result = ();
+
+    fn handle(&mut self, _: Fail, ctx: &mut Self::Context) {
+        ctx.stop_with(StopReason::Error("failed".to_owned()));
+    }
+}
+
+#[test]
+fn test_stop_reason() {
+    let sys = System::new("test");
+    let reasons = Arc::new(Mutex::new(Vec::new()));
+
+    let addr: Addr<Syn, _> = Reasons(Arc::clone(&reasons)).start();
+    addr.do_send(Fail);
+    sys.run();
+
+    let failed = StopReason::Error("failed".to_owned());
+    assert_eq!(*reasons.lock().unwrap(), vec![failed.clone(), failed]);
+}
+
+#[test]
+fn test_stop_reason_shutdown() {
+    let sys = System::new("test");
+    let reasons = Arc::new(Mutex::new(Vec::new()));
+
+    let _addr: Addr<Syn, _> = Reasons(Arc::clone(&reasons)).start();
+    Arbiter::system().do_send(actix::msgs::SystemExit(0));
+    sys.run();
+
+    assert_eq!(*reasons.lock().unwrap(), vec![StopReason::Shutdown, StopReason::Shutdown]);
+}
@@ -0,0 +1,80 @@
+extern crate actix;
+
+use std::thread;
+use std::time::Duration;
+use actix::prelude::*;
+use actix::testkit::TestProbe;
+use actix::watchdog::{Watchdog, WatchdogEvent};
+
+struct Sleep(u64);
+
+impl Message for Sleep {
+    type Result = ();
+}
+
+struct Sleeper;
+
+impl Actor for Sleeper {
+    type Context = Context<Self>;
+}
+
+impl Handler<Sleep> for Sleeper {
+    type Result = ();
+
+    fn handle(&mut self, msg: Sleep, _: &mut Self::Context) {
+        thread::sleep(Duration::from_millis(msg.0));
+    }
+}
+
+#[test]
+fn test_slow_handler() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::new();
+
+    Watchdog::new()
+        .threshold(Duration::from_millis(20))
+        .notify(probe.recipient())
+        .start();
+
+    let addr: Addr<Syn, _> = Sleeper.start();
+    addr.do_send(Sleep(0));
+    addr.do_send(Sleep(50));
+
+    match probe.expect_msg(&mut sys) {
+        WatchdogEvent::Slow{actor, message, elapsed, ..} => {
+            assert!(actor.ends_with("Sleeper"));
+            assert!(message.unwrap().ends_with("Sleep"));
+            assert!(elapsed >= Duration::from_millis(50));
+        }
+        ev => panic!("Unexpected event: {:?}", ev),
+    }
+    probe.expect_no_msg(&mut sys, Duration::from_millis(50));
+}
+
+#[test]
+fn test_stuck_arbiter() {
+    let mut sys = System::new("test");
+    let mut probe = TestProbe::new();
+
+    Watchdog::new()
+        .threshold(Duration::from_secs(10))
+        .deadline(Duration::from_millis(100))
+        .notify(probe.recipient())
+        .start();
+
+    let addr: Addr<Syn, _> = Arbiter::start(|_| Sleeper);
+    addr.do_send(Sleep(1000));
+
+    // system arbiter could be reported while test does not run its loop
+    for _ in 0..5 {
+        if let WatchdogEvent::Stuck{arbiter, actor, message, idle} = probe.expect_msg(&mut sys) {
+            if actor.map(|actor| actor.ends_with("Sleeper")).unwrap_or(false) {
+                assert_ne!(arbiter, Arbiter::name());
+                assert!(message.unwrap().ends_with("Sleep"));
+                assert!(idle > Duration::from_millis(100));
+                return
+            }
+        }
+    }
+    panic!("Stuck arbiter is not reported");
+}